@@ -0,0 +1,131 @@
+use std::fmt::Display;
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    NoSuchStack(usize),
+    NotEnoughCrates { stack: usize, available: usize, requested: usize },
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::NoSuchStack(stack) => write!(f, "there is no stack {stack}"),
+            MoveError::NotEnoughCrates { stack, available, requested } => write!(f, "stack {stack} holds {available} crates, {requested} requested"),
+        }
+    }
+}
+
+pub trait Crane {
+    fn lift_size(&self, remaining: usize) -> usize;
+    fn arrange(&self, _lifted: &mut [String]) {}
+
+    fn apply(&self, ship: &mut Ship, some_move: &Move) -> Result<(), MoveError> {
+        ship.validate(some_move)?;
+
+        let mut remaining = some_move.amount;
+        while remaining > 0 {
+            let lift_size = self.lift_size(remaining).clamp(1, remaining);
+            let from = &mut ship.stacks[some_move.from - 1];
+            let mut lifted = from.split_off(from.len() - lift_size);
+            self.arrange(&mut lifted);
+            ship.stacks[some_move.to - 1].append(&mut lifted);
+            remaining -= lift_size;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn lift_size(&self, _remaining: usize) -> usize {
+        1
+    }
+}
+
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn lift_size(&self, remaining: usize) -> usize {
+        remaining
+    }
+}
+
+pub struct LimitedCapacity(pub usize);
+
+impl Crane for LimitedCapacity {
+    fn lift_size(&self, _remaining: usize) -> usize {
+        self.0
+    }
+}
+
+pub struct BatchFlip(pub usize);
+
+impl Crane for BatchFlip {
+    fn lift_size(&self, remaining: usize) -> usize {
+        remaining
+    }
+
+    fn arrange(&self, lifted: &mut [String]) {
+        let unflipped = lifted.len() - self.0.min(lifted.len());
+        lifted[unflipped..].reverse();
+    }
+}
+
+pub fn parse_crane(s: &str) -> Result<Box<dyn Crane>, ()> {
+    match s.split_once('=') {
+        None if s == "9000" => Ok(Box::new(CrateMover9000)),
+        None if s == "9001" => Ok(Box::new(CrateMover9001)),
+        Some(("limited", capacity)) => Ok(Box::new(LimitedCapacity(capacity.parse().map_err(|_| ())?))),
+        Some(("flip", batch_size)) => Ok(Box::new(BatchFlip(batch_size.parse().map_err(|_| ())?))),
+        _ => Err(()),
+    }
+}
+
+pub struct History {
+    ship: Ship,
+    undo_stack: Vec<Ship>,
+    redo_stack: Vec<Ship>,
+}
+
+impl History {
+    pub fn new(ship: Ship) -> Self {
+        Self { ship, undo_stack: Vec::new(), redo_stack: Vec::new() }
+    }
+
+    pub fn ship(&self) -> &Ship {
+        &self.ship
+    }
+
+    pub fn apply(&mut self, crane: &dyn Crane, some_move: &Move) -> Result<(), MoveError> {
+        let mut next = self.ship.clone();
+        crane.apply(&mut next, some_move)?;
+
+        self.undo_stack.push(std::mem::replace(&mut self.ship, next));
+        self.redo_stack.clear();
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some(previous) => {
+                self.redo_stack.push(std::mem::replace(&mut self.ship, previous));
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        match self.redo_stack.pop() {
+            Some(next) => {
+                self.undo_stack.push(std::mem::replace(&mut self.ship, next));
+                true
+            },
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::*;
+
+struct Token {
+    center: usize,
+    text: String,
+}
+
+impl Token {
+    fn new(start: usize, text: String) -> Self {
+        Self { center: 2 * start + text.chars().count() - 1, text }
+    }
+}
+
+fn label_tokens(line: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (column, c) in line.chars().enumerate() {
+        match (c.is_whitespace(), &mut current) {
+            (false, Some((_, text))) => text.push(c),
+            (false, None) => current = Some((column, c.to_string())),
+            (true, Some(_)) => {
+                let (start, text) = current.take().unwrap();
+                tokens.push(Token::new(start, text));
+            },
+            (true, None) => {},
+        }
+    }
+
+    if let Some((start, text)) = current {
+        tokens.push(Token::new(start, text));
+    }
+
+    tokens
+}
+
+fn crate_tokens(line: &str) -> Result<Vec<Token>, ()> {
+    let mut tokens = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+
+    for (column, c) in line.chars().enumerate() {
+        match (c, &mut current) {
+            (']', Some((_, label))) if !label.is_empty() => {
+                let (start, label) = current.take().unwrap();
+                tokens.push(Token::new(start, format!("[{label}]")));
+            },
+            ('[' | ']', _) => {
+                if current.is_some() || c == ']' {
+                    return Err(());
+                }
+                current = Some((column, String::new()));
+            },
+            (c, Some((_, label))) if !c.is_whitespace() => label.push(c),
+            (c, None) if c.is_whitespace() => {},
+            _ => return Err(()),
+        }
+    }
+
+    match current {
+        None => Ok(tokens),
+        Some(_) => Err(()),
+    }
+}
+
+impl FromStr for Ship {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.lines().filter(|line| !line.trim().is_empty()).collect();
+        let (label_line, crate_lines) = lines.split_last().ok_or(())?;
+
+        let labels = label_tokens(label_line);
+        for (i, label) in labels.iter().enumerate() {
+            if label.text.parse() != Ok(i + 1) {
+                return Err(());
+            }
+        }
+
+        let mut stacks: Vec<Vec<String>> = vec![Vec::new(); labels.len()];
+
+        for (level, line) in crate_lines.iter().rev().enumerate() {
+            for token in crate_tokens(line)? {
+                let stack = labels.iter().enumerate()
+                    .min_by_key(|(_, label)| label.center.abs_diff(token.center))
+                    .map(|(stack, _)| stack)
+                    .ok_or(())?;
+
+                if stacks[stack].len() != level {
+                    return Err(());
+                }
+                stacks[stack].push(token.text[1..token.text.len() - 1].to_owned());
+            }
+        }
+
+        Ok(Ship::new(stacks))
+    }
+}
+
+fn centered(text: &str, width: usize) -> String {
+    let padding = width - text.chars().count();
+    format!("{}{text}{}", " ".repeat(padding / 2), " ".repeat(padding - padding / 2))
+}
+
+impl Display for Ship {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let widths: Vec<usize> = self.stacks.iter().enumerate().map(|(i, stack)| {
+            let widest_crate = stack.iter().map(|label| label.chars().count() + 2).max().unwrap_or(3);
+            widest_crate.max(3).max((i + 1).to_string().len())
+        }).collect();
+        let height = self.stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+
+        for level in (0..height).rev() {
+            let cells: Vec<String> = self.stacks.iter().zip(&widths).map(|(stack, &width)| {
+                match stack.get(level) {
+                    Some(label) => centered(&format!("[{label}]"), width),
+                    None => " ".repeat(width),
+                }
+            }).collect();
+            writeln!(f, "{}", cells.join(" ").trim_end())?;
+        }
+
+        let numbers: Vec<String> = widths.iter().enumerate().map(|(i, &width)| centered(&(i + 1).to_string(), width)).collect();
+        writeln!(f, "{}", numbers.join(" ").trim_end())
+    }
+}
@@ -1,53 +1,133 @@
-use std::{path::Path, fs};
+use std::{path::Path, fs, io};
+
+use crane::*;
+use optimizer::*;
+
+mod crane;
+mod drawing;
+mod optimizer;
 
 fn main() {
     part_one();
     part_two();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let render = args.iter().any(|arg| arg == "--render");
+    let optimize = args.iter().any(|arg| arg == "--optimize");
+    let step = args.iter().any(|arg| arg == "--step");
+    let crane = args.iter().find(|arg| !arg.starts_with("--"));
+
+    if crane.is_some() || render || optimize || step {
+        let crane = parse_crane(crane.map_or("9000", |crane| crane)).expect("crane model expected");
+        if optimize {
+            optimize_moves(crane.as_ref());
+        } else if step {
+            step_through_moves(crane.as_ref());
+        } else {
+            custom_crane(crane.as_ref(), render);
+        }
+    }
 }
 
 fn read_input() -> (Ship, Vec<Move>) {
     let path = Path::new("res/input");
     let contents = fs::read_to_string(path).expect("input file needed");
 
-    let (ship_input, moves_input) = contents.split_at(contents.find("\n\n").unwrap());
+    input_to_ship_and_moves(&contents)
+}
 
-    let ship_lines: Vec<&str> = ship_input.split("\n").collect();
-    let ship_lines_trimmed = &ship_lines[0..ship_lines.len() - 1];
-    let row_count = (ship_lines.last().unwrap().len() + 1) / 4;
-    let stacks: Vec<Vec<char>> = (0..=row_count).into_iter().map(|row| extract_row(row, ship_lines_trimmed)).collect();
+fn input_to_ship_and_moves(contents: &str) -> (Ship, Vec<Move>) {
+    let (ship_input, moves_input) = contents.split_at(contents.find("\n\n").unwrap());
 
-    let moves = moves_input.split("\n").filter(|line| !line.is_empty()).map(|line| line_to_move(line)).collect();
+    let ship = ship_input.parse().expect("stack drawing expected");
+    let moves = moves_input.split("\n").filter(|line| !line.is_empty()).map(line_to_move).collect();
 
-    (Ship::new(stacks), moves)
+    (ship, moves)
 }
 
-fn extract_row(row: usize, ship_lines_trimmed: &[&str]) -> Vec<char> {
-    ship_lines_trimmed.iter().map(|line| char_for_row(row, line)).filter(|c| *c != ' ').rev().collect()
+fn line_to_move(line: &str) -> Move {
+    let parts: Vec<&str> = line.split(" ").collect();
+    Move::new(parts[3].parse().unwrap(), parts[5].parse().unwrap(), parts[1].parse().unwrap())
 }
 
-fn char_for_row(row: usize, line: &str) -> char {
-    line.chars().nth(row * 4 + 1).unwrap_or(' ')
+fn operate_crane(ship: Ship, moves: &[Move], crane: &dyn Crane) -> Result<History, MoveError> {
+    let mut history = History::new(ship);
+    for some_move in moves {
+        history.apply(crane, some_move)?;
+    }
+
+    Ok(history)
 }
 
-fn line_to_move(line: &str) -> Move {
-    let parts: Vec<&str> = line.split(" ").collect();
-    Move::new(parts[3].parse().unwrap(), parts[5].parse().unwrap(), parts[1].parse().unwrap())
+fn optimize_moves(crane: &dyn Crane) {
+    let (ship, moves) = read_input();
+
+    match optimize(&ship, &moves, crane) {
+        Ok(optimized) => {
+            println!("Moves: {} -> {}", moves.len(), optimized.len());
+            optimized.iter().for_each(|some_move| println!("move {} from {} to {}", some_move.amount, some_move.from, some_move.to));
+        },
+        Err(error) => println!("Impossible move: {error}"),
+    }
 }
 
 fn part_one() {
-    let (mut ship, moves) = read_input();
-    moves.iter().for_each(|some_move| ship.apply_sequential_move(&some_move));
+    let (ship, moves) = read_input();
+    let history = operate_crane(ship, &moves, &CrateMover9000).unwrap_or_else(|error| panic!("{error}"));
 
-    println!("Tops: {}", ship.get_tops());
+    println!("Tops: {}", history.ship().get_tops());
 }
 
 fn part_two() {
-    let (mut ship, moves) = read_input();
-    moves.iter().for_each(|some_move| ship.apply_multi_move(&some_move));
+    let (ship, moves) = read_input();
+    let history = operate_crane(ship, &moves, &CrateMover9001).unwrap_or_else(|error| panic!("{error}"));
+
+    println!("Tops: {}", history.ship().get_tops());
+}
+
+fn custom_crane(crane: &dyn Crane, render: bool) {
+    let (ship, moves) = read_input();
+    let mut history = History::new(ship);
+
+    for some_move in &moves {
+        if let Err(error) = history.apply(crane, some_move) {
+            println!("Impossible move: {error}");
+            return;
+        }
+        if render {
+            println!("{}", history.ship());
+        }
+    }
+
+    println!("Tops: {}", history.ship().get_tops());
+}
+
+fn step_through_moves(crane: &dyn Crane) {
+    let (ship, moves) = read_input();
+    let mut history = operate_crane(ship, &moves, crane).unwrap_or_else(|error| panic!("{error}"));
+    while history.undo() {}
+
+    println!("{}", history.ship());
+    for line in io::stdin().lines() {
+        let stepped = match line.expect("readable stdin needed").trim() {
+            "" | "n" | "next" => history.redo(),
+            "p" | "prev" => history.undo(),
+            "q" | "quit" => break,
+            command => {
+                println!("Unknown command: {command}");
+                continue;
+            },
+        };
 
-    println!("Tops: {}", ship.get_tops());
+        if stepped {
+            println!("{}", history.ship());
+        } else {
+            println!("No more moves in that direction");
+        }
+    }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 struct Move {
     from: usize,
     to: usize,
@@ -60,33 +140,160 @@ impl Move {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 struct Ship {
-    stacks: Vec<Vec<char>>
+    stacks: Vec<Vec<String>>
 }
 
 impl Ship {
-    pub fn new(stacks: Vec<Vec<char>>) -> Self {
+    pub fn new(stacks: Vec<Vec<String>>) -> Self {
         Self { stacks }
     }
 
-    pub fn apply_multi_move(&mut self, some_move: &Move) {
-        let mut take_off: Vec<char> = (0..some_move.amount).map(|_| self.stacks[some_move.from - 1].pop().unwrap()).collect();
-        take_off.reverse();
-        self.stacks[some_move.to - 1].append(&mut take_off);
-    }
+    pub fn validate(&self, some_move: &Move) -> Result<(), MoveError> {
+        for stack in [some_move.from, some_move.to] {
+            if stack == 0 || stack > self.stacks.len() {
+                return Err(MoveError::NoSuchStack(stack));
+            }
+        }
 
-    pub fn apply_sequential_move(&mut self, some_move: &Move) {
-        for _ in 0..some_move.amount {
-            self.single_move(some_move.from, some_move.to);
+        let available = self.stacks[some_move.from - 1].len();
+        if some_move.amount > available {
+            Err(MoveError::NotEnoughCrates { stack: some_move.from, available, requested: some_move.amount })
+        } else {
+            Ok(())
         }
     }
 
-    fn single_move(&mut self, from: usize, to: usize) {
-        let from_letter = self.stacks[from - 1].pop().unwrap();
-        self.stacks[to - 1].push(from_letter);
+    fn get_tops(&self) -> String {
+        self.stacks.iter().filter_map(|stack| stack.last().map(String::as_str)).collect()
     }
+}
 
-    fn get_tops(&self) -> String {
-        self.stacks.iter().map(|stack| stack[stack.len() - 1]).collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = r#"    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+"#;
+
+    #[test]
+    fn crane_test() {
+        let (ship, moves) = input_to_ship_and_moves(INPUT);
+
+        assert_eq!(operate_crane(ship.clone(), &moves, &CrateMover9000).unwrap().ship().get_tops(), "CMZ");
+        assert_eq!(operate_crane(ship.clone(), &moves, &CrateMover9001).unwrap().ship().get_tops(), "MCD");
+        assert_eq!(operate_crane(ship.clone(), &moves, &LimitedCapacity(1)).unwrap().ship().get_tops(), "CMZ");
+        assert_eq!(operate_crane(ship.clone(), &moves, &LimitedCapacity(100)).unwrap().ship().get_tops(), "MCD");
+        assert_eq!(operate_crane(ship.clone(), &moves, &BatchFlip(0)).unwrap().ship().get_tops(), "MCD");
+
+        let mut flipped: Ship = "[E]\n[D]\n[C]\n[B]\n[A]\n 1   2".parse().unwrap();
+        BatchFlip(2).apply(&mut flipped, &Move::new(1, 2, 5)).unwrap();
+        assert_eq!(flipped.stacks[1], ["A", "B", "C", "E", "D"]);
+        LimitedCapacity(2).apply(&mut flipped, &Move::new(2, 1, 5)).unwrap();
+        assert_eq!(flipped.stacks[0], ["E", "D", "B", "C", "A"]);
+
+        assert_eq!(CrateMover9001.apply(&mut flipped, &Move::new(2, 1, 1)), Err(MoveError::NotEnoughCrates { stack: 2, available: 0, requested: 1 }));
+        assert_eq!(CrateMover9000.apply(&mut flipped, &Move::new(1, 4, 1)), Err(MoveError::NoSuchStack(4)));
+        assert_eq!(flipped.stacks[0].len(), 5);
+    }
+
+    #[test]
+    fn history_test() {
+        let (ship, moves) = input_to_ship_and_moves(INPUT);
+        let mut history = operate_crane(ship.clone(), &moves, &CrateMover9000).unwrap();
+        let final_ship = history.ship().clone();
+
+        assert!(history.undo());
+        assert!(history.undo());
+        assert_eq!(history.ship().get_tops(), "CZ");
+        assert!(history.redo());
+        assert!(history.redo());
+        assert!(!history.redo());
+        assert_eq!(*history.ship(), final_ship);
+
+        while history.undo() {}
+        assert_eq!(*history.ship(), ship);
+
+        assert!(history.apply(&CrateMover9000, &Move::new(3, 1, 2)).is_err());
+        assert_eq!(*history.ship(), ship);
+        history.apply(&CrateMover9000, &Move::new(3, 1, 1)).unwrap();
+        assert!(!history.redo());
+    }
+
+    #[test]
+    fn drawing_test() {
+        let (ship, moves) = input_to_ship_and_moves(INPUT);
+        assert_eq!(ship.to_string(), INPUT.split("\n\n").next().unwrap().to_owned() + "\n");
+
+        let mut history = History::new(ship);
+        history.apply(&CrateMover9000, &moves[0]).unwrap();
+        assert_eq!(history.ship().to_string(), "[D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n");
+
+        let wide = r#"
+      [KL]
+[AB]  [M]                                [Q]
+[C]   [NOP]                   [X]        [R]
+ 1     2    3  4  5  6  7  8   9  10  11  12
+"#;
+        let wide_ship: Ship = wide.parse().unwrap();
+        assert_eq!(wide_ship.stacks.len(), 12);
+        assert_eq!(wide_ship.stacks[0], ["C", "AB"]);
+        assert_eq!(wide_ship.stacks[1], ["NOP", "M", "KL"]);
+        assert_eq!(wide_ship.stacks[8], ["X"]);
+        assert_eq!(wide_ship.stacks[11], ["R", "Q"]);
+        assert_eq!(wide_ship.get_tops(), "ABKLXQ");
+        assert_eq!(wide_ship.to_string().parse::<Ship>(), Ok(wide_ship));
+
+        assert!("[A]\n 1   2\n".parse::<Ship>().is_ok());
+        assert!("[A]\n 2\n".parse::<Ship>().is_err());
+        assert!("[A] [B\n 1   2\n".parse::<Ship>().is_err());
+        assert!("[A]\n    \n 1\n".parse::<Ship>().is_ok());
+        assert!("    [A]\n[B]\n 1   2\n".parse::<Ship>().is_err());
+    }
+
+    #[test]
+    fn optimizer_test() {
+        let (ship, moves) = input_to_ship_and_moves(INPUT);
+
+        let optimized = optimize(&ship, &moves, &CrateMover9000).unwrap();
+        assert_eq!(optimized, moves);
+        assert!(is_equivalent(&ship, &moves, &optimized, &CrateMover9000));
+        assert_eq!(simulate(&ship, &optimized, &CrateMover9000).unwrap().get_tops(), "CMZ");
+
+        let redundant = vec![Move::new(1, 2, 1), Move::new(2, 1, 1), Move::new(2, 3, 1), Move::new(2, 3, 1), Move::new(3, 1, 1)];
+        let optimized = optimize(&ship, &redundant, &CrateMover9001).unwrap();
+        assert!(is_equivalent(&ship, &redundant, &optimized, &CrateMover9001));
+        assert_eq!(optimized, [Move::new(2, 3, 1), Move::new(2, 1, 1)]);
+        assert!(optimize(&ship, &[Move::new(3, 1, 2)], &CrateMover9000).is_err());
+
+        let crafted = r#"[C]
+[B] [E]
+[A] [D]
+ 1   2   3
+
+move 1 from 1 to 2
+move 1 from 2 to 1
+move 1 from 1 to 3
+move 1 from 1 to 3
+move 1 from 2 to 3
+move 1 from 3 to 1
+"#;
+        let (ship, moves) = input_to_ship_and_moves(crafted);
+        let optimized = optimize(&ship, &moves, &CrateMover9000).unwrap();
+        assert_eq!(optimized, [Move::new(1, 3, 2), Move::new(2, 1, 1)]);
+
+        let original_ship = simulate(&ship, &moves, &CrateMover9000).unwrap();
+        let optimized_ship = simulate(&ship, &optimized, &CrateMover9000).unwrap();
+        assert_eq!(optimized_ship, original_ship);
+        assert_eq!(optimized_ship.stacks, [vec!["A", "E"], vec!["D"], vec!["C", "B"]]);
     }
 }
@@ -0,0 +1,76 @@
+use super::*;
+
+pub fn simulate(ship: &Ship, moves: &[Move], crane: &dyn Crane) -> Result<Ship, MoveError> {
+    let mut ship = ship.clone();
+    for some_move in moves {
+        crane.apply(&mut ship, some_move)?;
+    }
+
+    Ok(ship)
+}
+
+pub fn is_equivalent(ship: &Ship, original: &[Move], candidate: &[Move], crane: &dyn Crane) -> bool {
+    match (simulate(ship, original, crane), simulate(ship, candidate, crane)) {
+        (Ok(original_ship), Ok(candidate_ship)) => original_ship == candidate_ship,
+        _ => false,
+    }
+}
+
+pub fn optimize(ship: &Ship, moves: &[Move], crane: &dyn Crane) -> Result<Vec<Move>, MoveError> {
+    let target = simulate(ship, moves, crane)?;
+    let mut optimized = moves.to_vec();
+
+    loop {
+        let before = optimized.len();
+        optimized = optimization_pass(ship, optimized, crane, &target);
+        if optimized.len() == before {
+            break;
+        }
+    }
+
+    assert!(is_equivalent(ship, moves, &optimized, crane), "optimized moves diverge from the original");
+    Ok(optimized)
+}
+
+fn optimization_pass(ship: &Ship, moves: Vec<Move>, crane: &dyn Crane, target: &Ship) -> Vec<Move> {
+    let mut kept: Vec<Move> = Vec::new();
+    let mut state = ship.clone();
+    let mut remaining = moves;
+
+    while !remaining.is_empty() {
+        let accepted = candidates(&remaining).into_iter().find(|candidate| {
+            simulate(&state, candidate, crane).is_ok_and(|result| result == *target)
+        });
+
+        if let Some(candidate) = accepted {
+            remaining = candidate;
+        }
+
+        if let Some(some_move) = remaining.first() {
+            crane.apply(&mut state, some_move).expect("kept moves stay valid");
+            kept.push(remaining.remove(0));
+        }
+    }
+
+    kept
+}
+
+fn candidates(remaining: &[Move]) -> Vec<Vec<Move>> {
+    let mut candidates = vec![remaining[1..].to_vec()];
+
+    if let [first, second, rest @ ..] = remaining {
+        candidates.push(rest.to_vec());
+
+        if first.from == second.from && first.to == second.to {
+            let merged = Move::new(first.from, first.to, first.amount + second.amount);
+            candidates.push([&[merged], rest].concat());
+        }
+
+        if first.amount == second.amount && first.to == second.from {
+            let chained = Move::new(first.from, second.to, first.amount);
+            candidates.push([&[chained], rest].concat());
+        }
+    }
+
+    candidates
+}
@@ -0,0 +1,112 @@
+use std::{collections::{HashMap, VecDeque}, io::{self, BufRead}};
+
+use super::*;
+
+pub struct MarkerDetector {
+    length: usize,
+    window: VecDeque<char>,
+    counts: HashMap<char, usize>,
+    position: usize,
+    predicate: Box<dyn MarkerPredicate>,
+}
+
+impl MarkerDetector {
+    pub fn new(length: usize) -> Self {
+        Self::with_predicate(length, Box::new(Distinct))
+    }
+
+    pub fn with_predicate(length: usize, predicate: Box<dyn MarkerPredicate>) -> Self {
+        Self { length, window: VecDeque::with_capacity(length + 1), counts: HashMap::new(), position: 0, predicate }
+    }
+
+    pub fn window(&self) -> String {
+        self.window.iter().collect()
+    }
+
+    pub fn push(&mut self, c: char) -> Option<usize> {
+        self.position += 1;
+        self.window.push_back(c);
+        *self.counts.entry(c).or_insert(0) += 1;
+
+        if self.window.len() > self.length {
+            let oldest = self.window.pop_front().unwrap();
+            let count = self.counts.get_mut(&oldest).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&oldest);
+            }
+        }
+
+        if self.window.len() == self.length && self.predicate.matches(&self.window, &self.counts) {
+            Some(self.position)
+        } else {
+            None
+        }
+    }
+}
+
+pub struct Markers<I> {
+    chars: I,
+    detector: MarkerDetector,
+}
+
+impl<I: Iterator<Item = char>> Iterator for Markers<I> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let detector = &mut self.detector;
+        self.chars.by_ref().find_map(|c| detector.push(c))
+    }
+}
+
+pub fn markers<I: IntoIterator<Item = char>>(chars: I, length: usize) -> Markers<I::IntoIter> {
+    Markers { chars: chars.into_iter(), detector: MarkerDetector::new(length) }
+}
+
+pub struct Utf8Chars<I> {
+    bytes: I,
+}
+
+impl<I: Iterator<Item = io::Result<u8>>> Iterator for Utf8Chars<I> {
+    type Item = io::Result<char>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "stream is not valid utf-8");
+
+        let first = match self.bytes.next()? {
+            Ok(byte) => byte,
+            Err(error) => return Some(Err(error)),
+        };
+        let width = match first.leading_ones() {
+            0 => 1,
+            2..=4 => first.leading_ones() as usize,
+            _ => return Some(Err(invalid())),
+        };
+
+        let mut encoded = [first, 0, 0, 0];
+        for byte in encoded.iter_mut().take(width).skip(1) {
+            *byte = match self.bytes.next() {
+                Some(Ok(byte)) => byte,
+                Some(Err(error)) => return Some(Err(error)),
+                None => return Some(Err(invalid())),
+            };
+        }
+
+        Some(std::str::from_utf8(&encoded[..width]).map_err(|_| invalid()).and_then(|s| s.chars().next().ok_or_else(invalid)))
+    }
+}
+
+pub fn utf8_chars<I: IntoIterator<Item = io::Result<u8>>>(bytes: I) -> Utf8Chars<I::IntoIter> {
+    Utf8Chars { bytes: bytes.into_iter() }
+}
+
+pub fn read_markers(reader: impl BufRead, length: usize) -> impl Iterator<Item = io::Result<usize>> {
+    let mut detector = MarkerDetector::new(length);
+
+    utf8_chars(reader.bytes())
+        .filter(|c| !c.as_ref().is_ok_and(|c| c.is_whitespace()))
+        .filter_map(move |c| match c {
+            Ok(c) => detector.push(c).map(Ok),
+            Err(error) => Some(Err(error)),
+        })
+}
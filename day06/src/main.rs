@@ -1,49 +1,146 @@
-use std::{path::Path, fs, collections::HashSet};
+use std::{path::Path, fs::{self, File}, io::BufReader};
+
+use detector::*;
+use predicate::*;
+use report::*;
+
+mod detector;
+mod predicate;
+mod report;
+
+const PACKET_MARKER_LENGTH: usize = 4;
+const MESSAGE_MARKER_LENGTH: usize = 14;
 
 fn main() {
     part_one();
     part_two();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [flag] if flag == "--all" => all_markers(),
+        [flag, length, predicate] if flag == "--report" => {
+            let length = length.parse().expect("window length expected");
+            report(length, predicate);
+        },
+        _ => {},
+    }
 }
 
-fn read_input() -> String {
+fn open_input() -> BufReader<File> {
     let path = Path::new("res/input");
-    let contents = fs::read_to_string(path).expect("input file needed");
+    let file = File::open(path).expect("input file needed");
+
+    BufReader::new(file)
+}
 
-    contents.replace("\n", "")
+fn first_marker_index(length: usize) -> usize {
+    read_markers(open_input(), length).next()
+        .expect("no marker in input")
+        .expect("input file unreadable")
 }
 
 fn part_one() {
-    let sequence = read_input();
-    let packet_index = find_packet_marker_index(&sequence);
+    let packet_index = first_marker_index(PACKET_MARKER_LENGTH);
 
     println!("Packet index: {packet_index}");
 }
 
 fn part_two() {
-    let sequence = read_input();
-    let message_index = find_message_marker_index(&sequence);
+    let message_index = first_marker_index(MESSAGE_MARKER_LENGTH);
 
     println!("Message index: {message_index}");
 }
 
-fn find_packet_marker_index(sequence: &str) -> usize {
-    find_marker_index(sequence, 4)
+fn read_input_chars() -> Vec<char> {
+    let input = fs::read_to_string("res/input").expect("input file needed");
+
+    input.chars().filter(|c| !c.is_whitespace()).collect()
 }
 
-fn find_message_marker_index(sequence: &str) -> usize {
-    find_marker_index(sequence, 14)
+fn all_markers() {
+    for length in [PACKET_MARKER_LENGTH, MESSAGE_MARKER_LENGTH] {
+        let positions: Vec<usize> = markers(read_input_chars(), length).collect();
+
+        println!("Markers of length {length}: {} at {positions:?}", positions.len());
+    }
 }
 
-fn find_marker_index(sequence: &str, length: usize) -> usize {
-    for i in length..sequence.len() {
-        let marker = &sequence[i-length..i];
-        let mut char_set = HashSet::new();
-        marker.chars().for_each(|c| { char_set.insert(c); });
+fn report(length: usize, predicate: &str) {
+    let report = Report::analyze(read_input_chars(), length, predicate).expect("predicate expected");
+
+    print!("{report}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUTS: [(&str, usize, usize); 5] = [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+    ];
 
-        if char_set.len() == length {
-            return i;
+    #[test]
+    fn part_one_test() {
+        for (sequence, packet_index, _) in INPUTS {
+            assert_eq!(markers(sequence.chars(), PACKET_MARKER_LENGTH).next(), Some(packet_index));
         }
     }
 
-    return sequence.len();
+    #[test]
+    fn part_two_test() {
+        for (sequence, _, message_index) in INPUTS {
+            assert_eq!(markers(sequence.chars(), MESSAGE_MARKER_LENGTH).next(), Some(message_index));
+        }
+    }
+
+    #[test]
+    fn streaming_test() {
+        let all: Vec<usize> = markers("abcabcaab".chars(), 3).collect();
+        assert_eq!(all, [3, 4, 5, 6, 7]);
+        assert_eq!(markers("aaaa".chars(), 2).next(), None);
+        assert_eq!(markers("ab".chars(), 1).count(), 2);
+
+        let reader = "mjqjpq\nmgbljsphdztnvjfqwrcgsmlb\n".as_bytes();
+        let first = read_markers(reader, MESSAGE_MARKER_LENGTH).next().unwrap().unwrap();
+        assert_eq!(first, 19);
+
+        let multibyte = "ääöüäß€😀x";
+        let positions: Vec<usize> = read_markers(multibyte.as_bytes(), 4).map(Result::unwrap).collect();
+        assert_eq!(positions, markers(multibyte.chars(), 4).collect::<Vec<_>>());
+        assert_eq!(positions, [6, 7, 8, 9]);
+
+        let invalid = [b'a', 0xc3, b'b', b'c', b'd'];
+        assert!(read_markers(&invalid[..], 2).any(|result| result.is_err()));
+        let truncated = [b'a', b'b', 0xe2, 0x82];
+        assert!(read_markers(&truncated[..], 2).any(|result| result.is_err()));
+    }
+
+    #[test]
+    fn predicate_test() {
+        let (sequence, packet_index, _) = INPUTS[0];
+        let distinct = Report::analyze(sequence.chars(), PACKET_MARKER_LENGTH, "distinct").unwrap();
+        assert_eq!(distinct.matches()[0], WindowMatch { end: packet_index, window: "jpqm".to_owned() });
+        assert_eq!(distinct.matches().len(), markers(sequence.chars(), PACKET_MARKER_LENGTH).count());
+
+        let repeats = Report::analyze("aabbbc".chars(), 3, "repeats=1").unwrap();
+        let ends: Vec<usize> = repeats.matches().iter().map(|window_match| window_match.end).collect();
+        assert_eq!(ends, [3, 4, 6]);
+
+        let charset = Report::analyze("xabbay".chars(), 2, "charset=ab").unwrap();
+        let windows: Vec<&str> = charset.matches().iter().map(|window_match| window_match.window.as_str()).collect();
+        assert_eq!(windows, ["ab", "bb", "ba"]);
+
+        let pattern = Report::analyze("abcabd".chars(), 3, "regex=^ab[^c]$").unwrap();
+        assert_eq!(pattern.matches(), [WindowMatch { end: 6, window: "abd".to_owned() }]);
+        assert_eq!(pattern.to_string(), "Windows of length 3 matching regex=^ab[^c]$: 1\n       6  abd\n");
+
+        assert!(parse_predicate("repeats=-1").is_err());
+        assert!(parse_predicate("regex=(").is_err());
+        assert!(parse_predicate("charset=").is_err());
+        assert!(parse_predicate("unique").is_err());
+    }
 }
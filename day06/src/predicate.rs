@@ -0,0 +1,49 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use regex::Regex;
+
+pub trait MarkerPredicate {
+    fn matches(&self, window: &VecDeque<char>, counts: &HashMap<char, usize>) -> bool;
+}
+
+pub struct Distinct;
+
+impl MarkerPredicate for Distinct {
+    fn matches(&self, window: &VecDeque<char>, counts: &HashMap<char, usize>) -> bool {
+        counts.len() == window.len()
+    }
+}
+
+pub struct AtMostRepeats(pub usize);
+
+impl MarkerPredicate for AtMostRepeats {
+    fn matches(&self, window: &VecDeque<char>, counts: &HashMap<char, usize>) -> bool {
+        window.len() - counts.len() <= self.0
+    }
+}
+
+pub struct CharacterSet(pub HashSet<char>);
+
+impl MarkerPredicate for CharacterSet {
+    fn matches(&self, _: &VecDeque<char>, counts: &HashMap<char, usize>) -> bool {
+        counts.keys().all(|c| self.0.contains(c))
+    }
+}
+
+pub struct Pattern(pub Regex);
+
+impl MarkerPredicate for Pattern {
+    fn matches(&self, window: &VecDeque<char>, _: &HashMap<char, usize>) -> bool {
+        self.0.is_match(&window.iter().collect::<String>())
+    }
+}
+
+pub fn parse_predicate(s: &str) -> Result<Box<dyn MarkerPredicate>, ()> {
+    match s.split_once('=') {
+        None if s == "distinct" => Ok(Box::new(Distinct)),
+        Some(("repeats", repeats)) => Ok(Box::new(AtMostRepeats(repeats.parse().map_err(|_| ())?))),
+        Some(("charset", characters)) if !characters.is_empty() => Ok(Box::new(CharacterSet(characters.chars().collect()))),
+        Some(("regex", pattern)) => Ok(Box::new(Pattern(Regex::new(pattern).map_err(|_| ())?))),
+        _ => Err(()),
+    }
+}
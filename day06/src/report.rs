@@ -0,0 +1,42 @@
+use std::fmt::Display;
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowMatch {
+    pub end: usize,
+    pub window: String,
+}
+
+pub struct Report {
+    length: usize,
+    predicate: String,
+    matches: Vec<WindowMatch>,
+}
+
+impl Report {
+    pub fn analyze(chars: impl IntoIterator<Item = char>, length: usize, predicate: &str) -> Result<Self, ()> {
+        let mut detector = MarkerDetector::with_predicate(length, parse_predicate(predicate)?);
+        let matches = chars.into_iter()
+            .filter_map(|c| detector.push(c).map(|end| WindowMatch { end, window: detector.window() }))
+            .collect();
+
+        Ok(Self { length, predicate: predicate.to_owned(), matches })
+    }
+
+    pub fn matches(&self) -> &[WindowMatch] {
+        &self.matches
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Windows of length {} matching {}: {}", self.length, self.predicate, self.matches().len())?;
+
+        for window_match in self.matches() {
+            writeln!(f, "{:>8}  {}", window_match.end, window_match.window)?;
+        }
+
+        Ok(())
+    }
+}
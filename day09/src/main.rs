@@ -1,8 +1,45 @@
-use std::{path::Path, fs, collections::HashSet, process::exit};
+use std::{path::{Path, PathBuf}, fs, process::exit, io, str::FromStr};
+
+use rope::*;
+use render::*;
+
+mod rope;
+mod render;
+
+const GIF_CELL_SIZE: u16 = 4;
 
 fn main() {
-    part_one();
-    part_two();
+    let options = options_from_args();
+    part_one(&options);
+    part_two(&options);
+
+    if options.frames_per_second.is_some() || options.gif_path.is_some() || options.frame.is_some() {
+        animate(&options);
+    }
+}
+
+#[derive(Default)]
+struct Options {
+    follow_rule: Option<String>,
+    frames_per_second: Option<u32>,
+    gif_path: Option<PathBuf>,
+    frame: Option<usize>,
+}
+
+fn options_from_args() -> Options {
+    let mut options = Options::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--animate" => options.frames_per_second = Some(args.next().and_then(|fps| fps.parse().ok()).expect("frames per second expected")),
+            "--gif" => options.gif_path = Some(args.next().expect("gif path expected").into()),
+            "--frame" => options.frame = Some(args.next().and_then(|frame| frame.parse().ok()).expect("frame number expected")),
+            rule => options.follow_rule = Some(rule.to_owned()),
+        }
+    }
+
+    options
 }
 
 fn read_input() -> String {
@@ -16,37 +53,74 @@ fn input_to_moves(input: &str) -> Vec<Move> {
 }
 
 fn line_to_move(line: &str) -> Move {
-    let direction = match line.chars().nth(0).unwrap() {
-        'U' => Direction::UP,
-        'D' => Direction::DOWN,
-        'L' => Direction::LEFT,
-        'R' => Direction::RIGHT,
-        _ => exit(1),
-    };
+    let (token, steps) = line.split_once(' ').unwrap_or_else(|| exit(1));
+    let direction = token.parse().unwrap_or_else(|_| exit(1));
+
+    Move::new(direction, steps.parse().unwrap())
+}
 
-    let steps = line.split(" ").nth(1).unwrap().parse().unwrap();
+fn visited_with_options(knot_count: usize, moves: &[Move], options: &Options) -> usize {
+    if moves.iter().any(|some_move| some_move.direction.dimensions() > 2) {
+        simulate::<3>(knot_count, moves, options)
+    } else {
+        simulate::<2>(knot_count, moves, options)
+    }
+}
+
+fn simulate<const D: usize>(knot_count: usize, moves: &[Move], options: &Options) -> usize {
+    let mut rope: Rope<D> = rope_with_options(knot_count, options);
+    moves.iter().for_each(|some_move| rope.apply_move(some_move));
+
+    rope.visited_count()
+}
 
-    Move::new(direction, steps)
+fn rope_with_options<const D: usize>(knot_count: usize, options: &Options) -> Rope<D> {
+    match &options.follow_rule {
+        Some(rule) => Rope::with_follow_rule(knot_count, parse_follow_rule(rule).expect("follow rule expected")),
+        None => Rope::new(knot_count),
+    }
 }
 
-fn part_one() {
+fn animate(options: &Options) {
     let input = read_input();
-    let mut rope = ShortRope::new();
     let moves = input_to_moves(&input);
+    let animation = animation_with_options(&moves, options).unwrap_or_else(|error| {
+        eprintln!("{error}");
+        exit(1)
+    });
+
+    if let Some(frame) = options.frame {
+        print!("{}", animation.render_frame(frame.min(animation.frame_count() - 1)));
+    }
+
+    if let Some(frames_per_second) = options.frames_per_second {
+        animation.play(&mut io::stdout(), frames_per_second).expect("terminal output failed");
+    }
 
-    moves.iter().for_each(|some_move| { rope.apply_move(&some_move); });
+    if let Some(gif_path) = &options.gif_path {
+        animation.write_gif(gif_path, GIF_CELL_SIZE, options.frames_per_second.unwrap_or(25)).expect("gif export failed");
+    }
+}
 
-    println!("Visited: {}", rope.visited_count());
+fn animation_with_options(moves: &[Move], options: &Options) -> Result<Animation, String> {
+    match moves.iter().map(|some_move| some_move.direction.dimensions()).max() {
+        Some(dimensions) if dimensions > 2 => Err(format!("cannot animate {dimensions}-dimensional moves")),
+        _ => Ok(Animation::record(rope_with_options(10, options), moves)),
+    }
 }
 
-fn part_two() {
+fn part_one(options: &Options) {
     let input = read_input();
-    let mut rope = LongRope::new(9);
     let moves = input_to_moves(&input);
 
-    moves.iter().for_each(|some_move| { rope.apply_move(&some_move); });
+    println!("Visited: {}", visited_with_options(2, &moves, options));
+}
+
+fn part_two(options: &Options) {
+    let input = read_input();
+    let moves = input_to_moves(&input);
 
-    println!("Visited: {}", rope.visited_count());
+    println!("Visited: {}", visited_with_options(10, &moves, options));
 }
 
 struct Move {
@@ -60,131 +134,57 @@ impl Move {
     }
 }
 
-enum Direction {
-    UP,
-    DOWN,
-    LEFT,
-    RIGHT,
-}
-
-trait Rope {
-    fn apply_move(&mut self, some_move: &Move);
-    fn visited_count(&self) -> usize;
-}
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Direction([i64; 3]);
 
-struct ShortRope {
-    head: (i64, i64),
-    tail: (i64, i64),
-    visited: HashSet<(i64, i64)>,
-}
+impl Direction {
+    pub const UP: Direction = Direction([0, 1, 0]);
+    pub const DOWN: Direction = Direction([0, -1, 0]);
+    pub const LEFT: Direction = Direction([-1, 0, 0]);
+    pub const RIGHT: Direction = Direction([1, 0, 0]);
+    pub const FORWARD: Direction = Direction([0, 0, 1]);
+    pub const BACKWARD: Direction = Direction([0, 0, -1]);
 
-impl ShortRope {
-    pub fn new() -> Self {
-        Self { head: (0, 0), tail: (0, 0), visited: HashSet::from([(0, 0)]) }
+    pub fn delta(&self) -> [i64; 3] {
+        self.0
     }
 
-    fn pull_tail(&mut self) {
-        let diff_x = self.head.0 - self.tail.0;
-        let diff_y = self.head.1 - self.tail.1;
-
-        if diff_x.abs() > 1 && diff_y.abs() > 0
-            || diff_x.abs() > 0 && diff_y.abs() > 1 {
-
-            self.tail.0 += diff_x.signum();
-            self.tail.1 += diff_y.signum();
-        } else {
-            if diff_x.abs() > 1 {
-                self.tail.0 += diff_x.signum();
-            } else if diff_y.abs() > 1 {
-                self.tail.1 += diff_y.signum();
-            }
-        }
-
-        self.visited.insert(self.tail);
+    pub fn dimensions(&self) -> usize {
+        self.0.iter().rposition(|&delta| delta != 0).map_or(0, |axis| axis + 1)
     }
 }
 
-impl Rope for ShortRope {
-    fn apply_move(&mut self, some_move: &Move) {
-        for _ in 0..some_move.steps {
-            match some_move.direction {
-                Direction::UP => self.head.1 += 1,
-                Direction::DOWN => self.head.1 -= 1,
-                Direction::LEFT => self.head.0 -= 1,
-                Direction::RIGHT => self.head.0 += 1,
+impl FromStr for Direction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut delta = [0; 3];
+
+        for c in s.chars() {
+            let single = match c {
+                'R' => Direction::RIGHT,
+                'L' => Direction::LEFT,
+                'U' => Direction::UP,
+                'D' => Direction::DOWN,
+                'F' => Direction::FORWARD,
+                'B' => Direction::BACKWARD,
+                _ => return Err(()),
+            };
+            let axis = single.dimensions() - 1;
+            if delta[axis] != 0 {
+                return Err(());
             }
-            self.pull_tail();
-        }
-    }
-
-    fn visited_count(&self) -> usize {
-        self.visited.len()
-    }
-}
-
-struct LongRope {
-    head: (i64, i64),
-    tail: Vec<(i64, i64)>,
-    visited: HashSet<(i64, i64)>,
-}
-
-impl LongRope {
-    pub fn new(length: usize) -> Self {
-        Self { head: (0, 0), tail: vec![(0, 0); length], visited: HashSet::from([(0, 0)]) }
-    }
-
-    fn pull_tail(&mut self) {
-        for i in 0..self.tail.len() {
-            self.pull_element(i);
+            delta[axis] = single.0[axis];
         }
 
-        self.visited.insert(*self.tail.last().unwrap());
-    }
-
-    fn pull_element(&mut self, n: usize) {
-        let previous_element = if n == 0 {
-            self.head
-        } else {
-            self.tail[n - 1]
-        };
-        let mut current_element = &mut self.tail[n];
-
-        let diff_x = previous_element.0 - current_element.0;
-        let diff_y = previous_element.1 - current_element.1;
-
-        if diff_x.abs() > 1 && diff_y.abs() > 0
-            || diff_x.abs() > 0 && diff_y.abs() > 1 {
-
-            current_element.0 += diff_x.signum();
-            current_element.1 += diff_y.signum();
+        if delta == [0; 3] {
+            Err(())
         } else {
-            if diff_x.abs() > 1 {
-                current_element.0 += diff_x.signum();
-            } else if diff_y.abs() > 1 {
-                current_element.1 += diff_y.signum();
-            }
+            Ok(Direction(delta))
         }
     }
 }
 
-impl Rope for LongRope {
-    fn apply_move(&mut self, some_move: &Move) {
-        for _ in 0..some_move.steps {
-            match some_move.direction {
-                Direction::UP => self.head.1 += 1,
-                Direction::DOWN => self.head.1 -= 1,
-                Direction::LEFT => self.head.0 -= 1,
-                Direction::RIGHT => self.head.0 += 1,
-            }
-            self.pull_tail();
-        }
-    }
-
-    fn visited_count(&self) -> usize {
-        self.visited.len()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,7 +211,7 @@ U 20
 
     #[test]
     fn part_one_test() {
-        let mut rope = ShortRope::new();
+        let mut rope: Rope = Rope::new(2);
         let moves = input_to_moves(PART_ONE_INPUT);
 
         moves.iter().for_each(|some_move| { rope.apply_move(&some_move); });
@@ -221,11 +221,99 @@ U 20
 
     #[test]
     fn part_two_test() {
-        let mut rope = LongRope::new(9);
+        let mut rope: Rope = Rope::new(10);
         let moves = input_to_moves(PART_TWO_INPUT);
 
         moves.iter().for_each(|some_move| { rope.apply_move(&some_move); });
 
         assert_eq!(rope.visited_count(), 36);
     }
+
+    #[test]
+    fn follow_rule_test() {
+        let moves = input_to_moves(PART_TWO_INPUT);
+        let mut rope: Rope = Rope::new(10);
+        moves.iter().for_each(|some_move| rope.apply_move(some_move));
+
+        assert_eq!(rope.visited(9).len(), rope.visited_count());
+        assert_eq!(rope.visited(1).len(), 88);
+        assert!(rope.visited(5).contains(&[0, 0]));
+        assert_eq!(rope.knots()[0], [-11, 15]);
+
+        let followed = |rule: &dyn FollowRule, leader: [i64; 2]| {
+            let mut follower = [0, 0];
+            rule.follow(&leader, &mut follower);
+            follower
+        };
+        assert_eq!(followed(&Manhattan, [1, 1]), [1, 0]);
+        assert_eq!(followed(&Manhattan, [0, 1]), [0, 0]);
+        assert_eq!(followed(&Chebyshev, [1, 1]), [0, 0]);
+        assert_eq!(followed(&Chebyshev, [2, 1]), [1, 1]);
+        assert_eq!(followed(&Slack(1), [2, 1]), [0, 0]);
+        assert_eq!(followed(&Slack(1), [3, 1]), [1, 1]);
+
+        assert!(parse_follow_rule("slack=x").is_err());
+        let mut lagging_rope: Rope = Rope::with_follow_rule(2, parse_follow_rule("slack=1").unwrap());
+        lagging_rope.apply_move(&Move::new(Direction::RIGHT, 4));
+        assert_eq!(lagging_rope.knots(), [[4, 0], [2, 0]]);
+        assert_eq!(lagging_rope.visited_count(), 3);
+    }
+
+    #[test]
+    fn multi_dimensional_test() {
+        assert_eq!("UR".parse(), Ok(Direction([1, 1, 0])));
+        assert_eq!("DLF".parse(), Ok(Direction([-1, -1, 1])));
+        assert_eq!("B".parse::<Direction>().unwrap().dimensions(), 3);
+        assert_eq!(Direction::RIGHT.dimensions(), 1);
+        assert!("UD".parse::<Direction>().is_err());
+        assert!("X".parse::<Direction>().is_err());
+
+        let mut diagonal_rope: Rope = Rope::new(3);
+        diagonal_rope.apply_move(&line_to_move("UR 3"));
+        assert_eq!(diagonal_rope.knots(), [[3, 3], [2, 2], [1, 1]]);
+
+        let moves = input_to_moves("F 3\nUR 2\nB 4\n");
+        let mut rope: Rope<3> = Rope::new(2);
+        moves.iter().for_each(|some_move| rope.apply_move(some_move));
+        assert_eq!(rope.knots(), [[2, 2, -1], [2, 2, 0]]);
+        assert_eq!(rope.visited_count(), 7);
+        assert_eq!(visited_with_options(2, &moves, &Options::default()), 7);
+
+        let mut chebyshev_4d: Rope<4> = Rope::new(2);
+        chebyshev_4d.apply_move(&Move::new(Direction::UP, 2));
+        assert_eq!(chebyshev_4d.knots()[1], [0, 1, 0, 0]);
+
+        assert_eq!(visited_with_options(10, &input_to_moves(PART_TWO_INPUT), &Options::default()), 36);
+
+        assert_eq!(animation_with_options(&moves, &Options::default()).err(), Some("cannot animate 3-dimensional moves".to_owned()));
+        assert_eq!(animation_with_options(&input_to_moves("UR 2\n"), &Options::default()).map(|animation| animation.frame_count()), Ok(3));
+    }
+
+    #[test]
+    fn render_test() {
+        let moves = input_to_moves(PART_ONE_INPUT);
+        let animation = Animation::record(Rope::new(2), &moves);
+
+        assert_eq!(animation.frame_count(), 25);
+        assert_eq!(animation.render_frame(4), r#"......
+......
+......
+......
+s##TH.
+"#);
+        assert_eq!(animation.render_frame(24), r#"..##..
+...##.
+.TH##.
+....#.
+s###..
+"#);
+
+        let long_animation = Animation::record(Rope::new(10), &input_to_moves(PART_TWO_INPUT)[..1]);
+        assert_eq!(long_animation.render_frame(5), "54321H\n");
+
+        let path = std::env::temp_dir().join("day09_render_test.gif");
+        animation.write_gif(&path, 2, 10).unwrap();
+        assert!(fs::read(&path).unwrap().starts_with(b"GIF89a"));
+        fs::remove_file(&path).unwrap();
+    }
 }
@@ -0,0 +1,160 @@
+use std::{collections::HashSet, fs::File, io::{self, Write}, path::Path, thread, time::Duration};
+
+use super::*;
+
+const BACKGROUND: u8 = 0;
+const VISITED: u8 = 1;
+const START: u8 = 2;
+const KNOT: u8 = 3;
+const HEAD: u8 = 4;
+const PALETTE: [u8; 15] = [
+    0x1e, 0x1e, 0x2e,
+    0x58, 0x5b, 0x70,
+    0x89, 0xb4, 0xfa,
+    0xf9, 0xe2, 0xaf,
+    0xf3, 0x8b, 0xa8,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    min: Position,
+    max: Position,
+}
+
+impl Bounds {
+    fn around(positions: impl Iterator<Item = Position>) -> Self {
+        positions.fold(Bounds { min: [0, 0], max: [0, 0] }, |bounds, position| Bounds {
+            min: [bounds.min[0].min(position[0]), bounds.min[1].min(position[1])],
+            max: [bounds.max[0].max(position[0]), bounds.max[1].max(position[1])],
+        })
+    }
+
+    fn width(&self) -> usize {
+        (self.max[0] - self.min[0] + 1) as usize
+    }
+
+    fn height(&self) -> usize {
+        (self.max[1] - self.min[1] + 1) as usize
+    }
+
+    fn rows(&self) -> impl Iterator<Item = i64> {
+        (self.min[1]..=self.max[1]).rev()
+    }
+
+    fn columns(&self) -> impl Iterator<Item = i64> {
+        self.min[0]..=self.max[0]
+    }
+}
+
+pub struct Animation {
+    frames: Vec<Vec<Position>>,
+    bounds: Bounds,
+}
+
+impl Animation {
+    pub fn record(mut rope: Rope, moves: &[Move]) -> Self {
+        let mut frames = vec![rope.knots().to_vec()];
+
+        for some_move in moves {
+            for _ in 0..some_move.steps {
+                rope.step(&some_move.direction);
+                frames.push(rope.knots().to_vec());
+            }
+        }
+
+        let bounds = Bounds::around(frames.iter().flatten().copied());
+        Self { frames, bounds }
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn render_frame(&self, index: usize) -> String {
+        let visited = self.frames[..=index].iter().map(|knots| *knots.last().unwrap()).collect();
+        render(&self.frames[index], &visited, self.bounds)
+    }
+
+    pub fn play(&self, out: &mut impl Write, frames_per_second: u32) -> io::Result<()> {
+        let delay = Duration::from_secs_f64(1.0 / frames_per_second.max(1) as f64);
+        let mut visited = HashSet::new();
+
+        for knots in &self.frames {
+            visited.insert(*knots.last().unwrap());
+            write!(out, "\x1b[2J\x1b[H{}", render(knots, &visited, self.bounds))?;
+            out.flush()?;
+            thread::sleep(delay);
+        }
+
+        Ok(())
+    }
+
+    pub fn write_gif(&self, path: &Path, cell_size: u16, frames_per_second: u32) -> io::Result<()> {
+        let cell_size = cell_size.max(1) as usize;
+        let too_large = || io::Error::new(io::ErrorKind::InvalidInput, "animation too large for a gif");
+        let width: u16 = (self.bounds.width() * cell_size).try_into().map_err(|_| too_large())?;
+        let height: u16 = (self.bounds.height() * cell_size).try_into().map_err(|_| too_large())?;
+
+        let mut encoder = gif::Encoder::new(File::create(path)?, width, height, &PALETTE).map_err(io::Error::other)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+
+        let delay = (100 / frames_per_second.clamp(1, 100)) as u16;
+        let mut visited = HashSet::new();
+
+        for knots in &self.frames {
+            visited.insert(*knots.last().unwrap());
+            let pixels = self.pixels(knots, &visited, cell_size);
+            let mut frame = gif::Frame::from_indexed_pixels(width, height, pixels, None);
+            frame.delay = delay;
+            encoder.write_frame(&frame).map_err(io::Error::other)?;
+        }
+
+        Ok(())
+    }
+
+    fn pixels(&self, knots: &[Position], visited: &HashSet<Position>, cell_size: usize) -> Vec<u8> {
+        let row_width = self.bounds.width() * cell_size;
+        let mut pixels = Vec::with_capacity(row_width * self.bounds.height() * cell_size);
+
+        for y in self.bounds.rows() {
+            let row: Vec<u8> = self.bounds.columns().flat_map(|x| {
+                let color = match cell_symbol(knots, visited, [x, y]) {
+                    'H' => HEAD,
+                    's' => START,
+                    '#' => VISITED,
+                    '.' => BACKGROUND,
+                    _ => KNOT,
+                };
+                std::iter::repeat_n(color, cell_size)
+            }).collect();
+
+            for _ in 0..cell_size {
+                pixels.extend_from_slice(&row);
+            }
+        }
+
+        pixels
+    }
+}
+
+fn render(knots: &[Position], visited: &HashSet<Position>, bounds: Bounds) -> String {
+    let mut output = String::with_capacity((bounds.width() + 1) * bounds.height());
+
+    for y in bounds.rows() {
+        output.extend(bounds.columns().map(|x| cell_symbol(knots, visited, [x, y])));
+        output.push('\n');
+    }
+
+    output
+}
+
+fn cell_symbol(knots: &[Position], visited: &HashSet<Position>, position: Position) -> char {
+    match knots.iter().position(|&knot| knot == position) {
+        Some(0) => 'H',
+        Some(_) if knots.len() == 2 => 'T',
+        Some(index) => char::from_digit(index as u32, 36).unwrap_or('*'),
+        None if position == [0, 0] => 's',
+        None if visited.contains(&position) => '#',
+        None => '.',
+    }
+}
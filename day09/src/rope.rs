@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+
+use super::*;
+
+pub type Position<const D: usize = 2> = [i64; D];
+
+pub trait FollowRule {
+    fn follow(&self, leader: &[i64], follower: &mut [i64]);
+}
+
+fn chebyshev_distance(leader: &[i64], follower: &[i64]) -> i64 {
+    leader.iter().zip(follower).map(|(lead, follow)| (lead - follow).abs()).max().unwrap_or(0)
+}
+
+fn step_towards(leader: &[i64], follower: &mut [i64]) {
+    follower.iter_mut().zip(leader).for_each(|(follow, lead)| *follow += (lead - *follow).signum());
+}
+
+pub struct Chebyshev;
+
+impl FollowRule for Chebyshev {
+    fn follow(&self, leader: &[i64], follower: &mut [i64]) {
+        if chebyshev_distance(leader, follower) > 1 {
+            step_towards(leader, follower);
+        }
+    }
+}
+
+pub struct Manhattan;
+
+impl FollowRule for Manhattan {
+    fn follow(&self, leader: &[i64], follower: &mut [i64]) {
+        let diffs: Vec<i64> = leader.iter().zip(follower.iter()).map(|(lead, follow)| lead - follow).collect();
+
+        if diffs.iter().map(|diff| diff.abs()).sum::<i64>() > 1 {
+            let (axis, diff) = diffs.iter().enumerate().rev().max_by_key(|(_, diff)| diff.abs()).unwrap();
+            follower[axis] += diff.signum();
+        }
+    }
+}
+
+pub struct Slack(pub i64);
+
+impl FollowRule for Slack {
+    fn follow(&self, leader: &[i64], follower: &mut [i64]) {
+        if chebyshev_distance(leader, follower) > 1 + self.0 {
+            step_towards(leader, follower);
+        }
+    }
+}
+
+pub fn parse_follow_rule(s: &str) -> Result<Box<dyn FollowRule>, ()> {
+    match s.split_once('=') {
+        None if s == "chebyshev" => Ok(Box::new(Chebyshev)),
+        None if s == "manhattan" => Ok(Box::new(Manhattan)),
+        Some(("slack", slack)) => Ok(Box::new(Slack(slack.parse().map_err(|_| ())?))),
+        _ => Err(()),
+    }
+}
+
+pub struct Rope<const D: usize = 2> {
+    knots: Vec<Position<D>>,
+    visited: Vec<HashSet<Position<D>>>,
+    follow_rule: Box<dyn FollowRule>,
+}
+
+impl<const D: usize> Rope<D> {
+    pub fn new(knot_count: usize) -> Self {
+        Self::with_follow_rule(knot_count, Box::new(Chebyshev))
+    }
+
+    pub fn with_follow_rule(knot_count: usize, follow_rule: Box<dyn FollowRule>) -> Self {
+        let knot_count = knot_count.max(1);
+        Self { knots: vec![[0; D]; knot_count], visited: vec![HashSet::from([[0; D]]); knot_count], follow_rule }
+    }
+
+    pub fn apply_move(&mut self, some_move: &Move) {
+        for _ in 0..some_move.steps {
+            self.step(&some_move.direction);
+        }
+    }
+
+    pub fn step(&mut self, direction: &Direction) {
+        assert!(direction.dimensions() <= D, "{}-dimensional move applied to {D}-dimensional rope", direction.dimensions());
+
+        let head = &mut self.knots[0];
+        head.iter_mut().zip(direction.delta()).for_each(|(coordinate, delta)| *coordinate += delta);
+        self.pull_knots();
+    }
+
+    pub fn visited_count(&self) -> usize {
+        self.visited(self.knots.len() - 1).len()
+    }
+
+    pub fn visited(&self, knot: usize) -> &HashSet<Position<D>> {
+        &self.visited[knot]
+    }
+
+    pub fn knots(&self) -> &[Position<D>] {
+        &self.knots
+    }
+
+    fn pull_knots(&mut self) {
+        self.visited[0].insert(self.knots[0]);
+
+        for i in 1..self.knots.len() {
+            let (leaders, followers) = self.knots.split_at_mut(i);
+            self.follow_rule.follow(&leaders[i - 1], &mut followers[0]);
+            self.visited[i].insert(self.knots[i]);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use super::*;
+
+impl Pack {
+    pub fn predicted_monkey_business(&self, panic_divider: usize, rounds: u64) -> u128 {
+        let mut inspection_counts = self.predicted_inspection_counts(panic_divider, rounds);
+        inspection_counts.sort();
+
+        inspection_counts.iter().rev().take(2).map(|&count| count as u128).product()
+    }
+
+    pub fn predicted_inspection_counts(&self, panic_divider: usize, rounds: u64) -> Vec<u64> {
+        let common_multiple = self.common_multiple(panic_divider);
+        let mut inspection_counts = vec![0; self.monkeys.len()];
+
+        for (monkey, holder) in self.monkeys.iter().enumerate() {
+            for &item in &holder.items {
+                let trajectory = self.item_trajectory(monkey, item, panic_divider, common_multiple, rounds);
+                trajectory.add_counts(&mut inspection_counts, rounds);
+            }
+        }
+
+        inspection_counts
+    }
+
+    fn item_trajectory(&self, monkey: usize, item: WorryLevel, panic_divider: usize, common_multiple: Option<usize>, rounds: u64) -> Trajectory {
+        let mut seen = HashMap::new();
+        let mut round_inspections = Vec::new();
+        let mut state = (monkey, item);
+
+        while (round_inspections.len() as u64) < rounds {
+            if let Some(&cycle_start) = seen.get(&state) {
+                return Trajectory { round_inspections, cycle_start };
+            }
+            seen.insert(state, round_inspections.len());
+
+            let (next_state, inspections) = self.item_round(state, panic_divider, common_multiple);
+            round_inspections.push(inspections);
+            state = next_state;
+        }
+
+        let cycle_start = round_inspections.len();
+        Trajectory { round_inspections, cycle_start }
+    }
+
+    fn item_round(&self, (mut monkey, mut item): (usize, WorryLevel), panic_divider: usize, common_multiple: Option<usize>) -> ((usize, WorryLevel), Vec<usize>) {
+        let mut inspections = Vec::new();
+
+        loop {
+            let (new_value, target) = self.monkeys[monkey].inspect(item, panic_divider, common_multiple);
+            inspections.push(monkey);
+            item = new_value;
+
+            if target > monkey {
+                monkey = target;
+            } else {
+                return ((target, item), inspections);
+            }
+        }
+    }
+}
+
+struct Trajectory {
+    round_inspections: Vec<Vec<usize>>,
+    cycle_start: usize,
+}
+
+impl Trajectory {
+    fn add_counts(&self, inspection_counts: &mut [u64], rounds: u64) {
+        let prefix = &self.round_inspections[..self.cycle_start];
+        let cycle = &self.round_inspections[self.cycle_start..];
+
+        Self::add_rounds(inspection_counts, prefix, 1);
+
+        if !cycle.is_empty() {
+            let remaining = rounds - prefix.len() as u64;
+            let full_cycles = remaining / cycle.len() as u64;
+            let partial_cycle = (remaining % cycle.len() as u64) as usize;
+
+            Self::add_rounds(inspection_counts, cycle, full_cycles);
+            Self::add_rounds(inspection_counts, &cycle[..partial_cycle], 1);
+        }
+    }
+
+    fn add_rounds(inspection_counts: &mut [u64], rounds: &[Vec<usize>], times: u64) {
+        rounds.iter().flatten().for_each(|&monkey| inspection_counts[monkey] += times);
+    }
+}
@@ -0,0 +1,169 @@
+use std::{str::FromStr, iter::Peekable, vec::IntoIter};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    Num(i128),
+    Old,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Value(Number),
+    Neg(Box<Expression>),
+    Add(Box<Expression>, Box<Expression>),
+    Sub(Box<Expression>, Box<Expression>),
+    Mult(Box<Expression>, Box<Expression>),
+    Div(Box<Expression>, Box<Expression>),
+    Rem(Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    pub fn evaluate(&self, old: i128) -> Option<i128> {
+        match self {
+            Expression::Value(Number::Num(num)) => Some(*num),
+            Expression::Value(Number::Old) => Some(old),
+            Expression::Neg(inner) => inner.evaluate(old)?.checked_neg(),
+            Expression::Add(left, right) => left.evaluate(old)?.checked_add(right.evaluate(old)?),
+            Expression::Sub(left, right) => left.evaluate(old)?.checked_sub(right.evaluate(old)?),
+            Expression::Mult(left, right) => left.evaluate(old)?.checked_mul(right.evaluate(old)?),
+            Expression::Div(left, right) => left.evaluate(old)?.checked_div(right.evaluate(old)?),
+            Expression::Rem(left, right) => left.evaluate(old)?.checked_rem(right.evaluate(old)?),
+        }
+    }
+
+    /// Whether the expression only uses ring operations, so evaluating it
+    /// on a residue gives the residue of evaluating it on the full value.
+    pub fn preserves_residues(&self) -> bool {
+        match self {
+            Expression::Value(_) => true,
+            Expression::Neg(inner) => inner.preserves_residues(),
+            Expression::Add(left, right) | Expression::Sub(left, right) | Expression::Mult(left, right) => {
+                left.preserves_residues() && right.preserves_residues()
+            },
+            Expression::Div(_, _) | Expression::Rem(_, _) => false,
+        }
+    }
+}
+
+impl FromStr for Expression {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = tokenize(s)?.into_iter().peekable();
+        let expression = parse_sum(&mut tokens)?;
+
+        match tokens.next() {
+            None => Ok(expression),
+            Some(_) => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(Number),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Open,
+    Close,
+}
+
+type Tokens = Peekable<IntoIter<Token>>;
+
+fn tokenize(s: &str) -> Result<Vec<Token>, ()> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' => { chars.next(); },
+            '+' => { chars.next(); tokens.push(Token::Plus); },
+            '-' => { chars.next(); tokens.push(Token::Minus); },
+            '*' => { chars.next(); tokens.push(Token::Star); },
+            '/' => { chars.next(); tokens.push(Token::Slash); },
+            '%' => { chars.next(); tokens.push(Token::Percent); },
+            '(' => { chars.next(); tokens.push(Token::Open); },
+            ')' => { chars.next(); tokens.push(Token::Close); },
+            '0'..='9' => {
+                let mut digits = String::new();
+                while let Some(&digit) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                    digits.push(digit);
+                    chars.next();
+                }
+                tokens.push(Token::Number(Number::Num(digits.parse().map_err(|_| ())?)));
+            },
+            'a'..='z' => {
+                let mut word = String::new();
+                while let Some(&letter) = chars.peek().filter(|c| c.is_ascii_lowercase()) {
+                    word.push(letter);
+                    chars.next();
+                }
+                match word.as_str() {
+                    "old" => tokens.push(Token::Number(Number::Old)),
+                    _ => return Err(()),
+                }
+            },
+            _ => return Err(()),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_sum(tokens: &mut Tokens) -> Result<Expression, ()> {
+    let mut expression = parse_product(tokens)?;
+
+    loop {
+        match tokens.peek() {
+            Some(Token::Plus) => {
+                tokens.next();
+                expression = Expression::Add(Box::new(expression), Box::new(parse_product(tokens)?));
+            },
+            Some(Token::Minus) => {
+                tokens.next();
+                expression = Expression::Sub(Box::new(expression), Box::new(parse_product(tokens)?));
+            },
+            _ => return Ok(expression),
+        }
+    }
+}
+
+fn parse_product(tokens: &mut Tokens) -> Result<Expression, ()> {
+    let mut expression = parse_unary(tokens)?;
+
+    loop {
+        match tokens.peek() {
+            Some(Token::Star) => {
+                tokens.next();
+                expression = Expression::Mult(Box::new(expression), Box::new(parse_unary(tokens)?));
+            },
+            Some(Token::Slash) => {
+                tokens.next();
+                expression = Expression::Div(Box::new(expression), Box::new(parse_unary(tokens)?));
+            },
+            Some(Token::Percent) => {
+                tokens.next();
+                expression = Expression::Rem(Box::new(expression), Box::new(parse_unary(tokens)?));
+            },
+            _ => return Ok(expression),
+        }
+    }
+}
+
+fn parse_unary(tokens: &mut Tokens) -> Result<Expression, ()> {
+    match tokens.next() {
+        Some(Token::Minus) => Ok(Expression::Neg(Box::new(parse_unary(tokens)?))),
+        Some(Token::Number(number)) => Ok(Expression::Value(number)),
+        Some(Token::Open) => {
+            let expression = parse_sum(tokens)?;
+            match tokens.next() {
+                Some(Token::Close) => Ok(expression),
+                _ => Err(()),
+            }
+        },
+        _ => Err(()),
+    }
+}
@@ -1,8 +1,19 @@
 use std::{path::Path, fs, str::FromStr, collections::VecDeque};
 
+use expression::*;
+use statistics::*;
+
+mod expression;
+mod cycle;
+mod statistics;
+
 fn main() {
     part_one();
     part_two();
+
+    if let Some(output_directory) = std::env::args().nth(1) {
+        export_statistics(Path::new(&output_directory));
+    }
 }
 
 fn read_input() -> String {
@@ -24,11 +35,30 @@ fn part_one() {
 }
 
 fn part_two() {
+    let input = read_input();
+    let pack = input_to_pack(&input);
+
+    println!("Monkey business: {}", pack.predicted_monkey_business(1, 10000));
+}
+
+fn export_statistics(output_directory: &Path) {
     let input = read_input();
     let mut pack = input_to_pack(&input);
-    pack_rounds(&mut pack, 1, 10000);
+    let statistics = pack_rounds_with_statistics(&mut pack, 1, 10000);
 
-    println!("Monkey business: {}", pack.monkey_business());
+    fs::write(output_directory.join("rounds.csv"), statistics.rounds_csv()).expect("writable output directory needed");
+    fs::write(output_directory.join("transfers.csv"), statistics.transfers_csv()).expect("writable output directory needed");
+    fs::write(output_directory.join("flow.dot"), statistics.flow_graph()).expect("writable output directory needed");
+}
+
+fn pack_rounds_with_statistics(pack: &mut Pack, panic_divider: usize, rounds: usize) -> PackStatistics {
+    let mut statistics = PackStatistics::new();
+
+    for _ in 0..rounds {
+        statistics.push(pack.round_with_statistics(panic_divider));
+    }
+
+    statistics
 }
 
 fn pack_rounds(pack: &mut Pack, panic_divider: usize, rounds: usize) {
@@ -37,6 +67,8 @@ fn pack_rounds(pack: &mut Pack, panic_divider: usize, rounds: usize) {
     }
 }
 
+type WorryLevel = i128;
+
 struct Pack {
     monkeys: Vec<Monkey>,
 }
@@ -54,35 +86,54 @@ impl Pack {
     }
 
     pub fn round(&mut self, panic_divider: usize) {
-        let common_multiplier = self.common_multiple();
+        self.play_round(panic_divider, None);
+    }
+
+    pub fn round_with_statistics(&mut self, panic_divider: usize) -> RoundStatistics {
+        let queue_lengths = self.monkeys.iter().map(|monkey| monkey.items.len()).collect();
+        let mut statistics = RoundStatistics::new(queue_lengths);
+        self.play_round(panic_divider, Some(&mut statistics));
+
+        statistics
+    }
+
+    fn play_round(&mut self, panic_divider: usize, mut statistics: Option<&mut RoundStatistics>) {
+        let common_multiplier = self.common_multiple(panic_divider);
 
         for i in 0..self.monkeys.len() {
             let inspected_list = self.monkeys[i].inspect_items(panic_divider, common_multiplier);
+            if let Some(statistics) = statistics.as_deref_mut() {
+                statistics.record_turn(i, &inspected_list);
+            }
             self.distribute_items(inspected_list);
         }
     }
 
-    fn distribute_items(&mut self, mut inspected_list: VecDeque<(usize, usize)>) {
+    fn distribute_items(&mut self, mut inspected_list: VecDeque<(WorryLevel, usize)>) {
         while !inspected_list.is_empty() {
             let (item, target) = inspected_list.pop_front().unwrap();
             self.monkeys[target].items.push_back(item);
         }
     }
 
-    fn common_multiple(&self) -> usize {
-        self.monkeys.iter().map(|monkey| monkey.test.divisible_by).product()
+    fn common_multiple(&self, panic_divider: usize) -> Option<usize> {
+        if panic_divider == 1 && self.monkeys.iter().all(|monkey| monkey.operation.preserves_residues()) {
+            Some(self.monkeys.iter().map(|monkey| monkey.test.divisible_by).product())
+        } else {
+            None
+        }
     }
 }
 
 struct Monkey {
-    items: VecDeque<usize>,
+    items: VecDeque<WorryLevel>,
     operation: Operation,
     test: Test,
     inspection_counter: usize,
 }
 
 impl Monkey {
-    pub fn inspect_items(&mut self, panic_divider: usize, common_multiple: usize) -> VecDeque<(usize, usize)> {
+    pub fn inspect_items(&mut self, panic_divider: usize, common_multiple: Option<usize>) -> VecDeque<(WorryLevel, usize)> {
         let mut inspected_list = VecDeque::new();
 
         while !self.items.is_empty() {
@@ -92,13 +143,20 @@ impl Monkey {
         inspected_list
     }
 
-    pub fn inspect_item(&mut self, panic_divider: usize, common_multiple: usize) -> (usize, usize) {
+    pub fn inspect_item(&mut self, panic_divider: usize, common_multiple: Option<usize>) -> (WorryLevel, usize) {
         let item = self.items.pop_front().unwrap();
-        let new_value = (self.operation.apply(item) / panic_divider) % common_multiple;
-        let target = self.test.evaluate(new_value);
-
         self.inspection_counter += 1;
 
+        self.inspect(item, panic_divider, common_multiple)
+    }
+
+    pub fn inspect(&self, item: WorryLevel, panic_divider: usize, common_multiple: Option<usize>) -> (WorryLevel, usize) {
+        let mut new_value = self.operation.apply(item).div_euclid(panic_divider as WorryLevel);
+        if let Some(common_multiple) = common_multiple {
+            new_value = new_value.rem_euclid(common_multiple as WorryLevel);
+        }
+        let target = self.test.evaluate(new_value);
+
         (new_value, target)
     }
 }
@@ -111,7 +169,7 @@ impl FromStr for Monkey {
         if lines.len() != 6 {
             Err(())
         } else {
-            let items: VecDeque<usize> = lines[1].split(" ").skip(4).map(|item| item.replace(",", "").parse().unwrap()).collect();
+            let items: VecDeque<WorryLevel> = lines[1].split(" ").skip(4).map(|item| item.replace(",", "").parse().unwrap()).collect();
             let operation = lines[2].parse().unwrap();
             let test = lines[3..=5].join("\n").parse().unwrap();
 
@@ -121,50 +179,25 @@ impl FromStr for Monkey {
 }
 
 #[derive(Debug, PartialEq)]
-enum Operation {
-    Add(Number),
-    Mult(Number),
-}
+struct Operation(Expression);
 
 impl Operation {
-    pub fn apply(&self, item: usize) -> usize {
-        match self {
-            Operation::Add(number) => {
-                match number {
-                    Number::Num(num) => item + num,
-                    Number::Old => item + item,
-                }
-            },
-            Operation::Mult(number) => {
-                match number {
-                    Number::Num(num) => item * num,
-                    Number::Old => item * item,
-                }
-            }
-        }
+    pub fn apply(&self, item: WorryLevel) -> WorryLevel {
+        self.0.evaluate(item).expect("operation overflowed or divided by zero")
     }
-}
 
-#[derive(Debug, PartialEq)]
-enum Number {
-    Num(usize),
-    Old
+    pub fn preserves_residues(&self) -> bool {
+        self.0.preserves_residues()
+    }
 }
 
 impl FromStr for Operation {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let pieces: Vec<&str> = s.trim().split(" ").collect();
-        let number = match pieces[5] {
-            "old" => Number::Old,
-            num => Number::Num(num.parse().unwrap()),
-        };
-
-        match pieces[4] {
-            "*" => Ok(Operation::Mult(number)),
-            "+" => Ok(Operation::Add(number)),
-            _ => Err(()),
+        match s.trim().strip_prefix("Operation:").and_then(|rest| rest.trim().strip_prefix("new =")) {
+            Some(expression) => Ok(Operation(expression.parse()?)),
+            None => Err(()),
         }
     }
 }
@@ -177,8 +210,8 @@ struct Test {
 }
 
 impl Test {
-    pub fn evaluate(&self, item: usize) -> usize {
-        if item % self.divisible_by == 0 {
+    pub fn evaluate(&self, item: WorryLevel) -> usize {
+        if item % self.divisible_by as WorryLevel == 0 {
             self.true_target
         } else {
             self.false_target
@@ -237,7 +270,7 @@ Monkey 3:
         let pack = input_to_pack(INPUT);
         assert_eq!(pack.monkeys.len(), 4);
         assert_eq!(pack.monkeys[0].items, [79, 98]);
-        assert_eq!(pack.monkeys[0].operation, Operation::Mult(Number::Num(19)));
+        assert_eq!(pack.monkeys[0].operation, Operation(Expression::Mult(Box::new(Expression::Value(Number::Old)), Box::new(Expression::Value(Number::Num(19))))));
         assert_eq!(pack.monkeys[0].test, Test { divisible_by: 23, true_target: 2, false_target: 3 });
     }
 
@@ -254,4 +287,88 @@ Monkey 3:
         pack_rounds(&mut pack, 1, 10000);
         assert_eq!(pack.monkey_business(), 2713310158);
     }
+
+    #[test]
+    fn expression_test() {
+        let operation: Operation = "  Operation: new = (old - 4) * -(old % 3) / 2 + 10".parse().unwrap();
+        assert_eq!(operation.apply(11), (11 - 4) * -(11 % 3) / 2 + 10);
+        assert_eq!(operation.apply(4), 10);
+
+        let expression: Expression = "old * old + 2 * (3 - old)".parse().unwrap();
+        assert_eq!(expression.evaluate(5), Some(21));
+        assert_eq!("old / (old - old)".parse::<Expression>().unwrap().evaluate(7), None);
+
+        assert!("old * ".parse::<Expression>().is_err());
+        assert!("(old + 1".parse::<Expression>().is_err());
+        assert!("new + 1".parse::<Expression>().is_err());
+
+        let cube: Monkey = "Monkey 0:\n  Starting items: 9699000\n  Operation: new = old * old * old\n  Test: divisible by 23\n    If true: throw to monkey 1\n    If false: throw to monkey 0".parse().unwrap();
+        assert_eq!(cube.inspect(9699000, 1, Some(9699690)), (9699000i128.pow(3) % 9699690, 0));
+
+        let mut halving = input_to_pack(INPUT);
+        halving.monkeys[1].operation = "Operation: new = old / 2 + 6".parse().unwrap();
+        assert_eq!(halving.common_multiple(1), None);
+        assert_eq!(halving.monkeys[1].inspect(75, 1, halving.common_multiple(1)), (43, 0));
+        assert_eq!(input_to_pack(INPUT).common_multiple(1), Some(23 * 19 * 13 * 17));
+        assert_eq!(input_to_pack(INPUT).common_multiple(3), None);
+
+        let mut shrinking = input_to_pack(INPUT);
+        shrinking.monkeys[3].operation = "Operation: new = old / 2 - 10".parse().unwrap();
+        assert_eq!(shrinking.monkeys[3].inspect(4, 1, shrinking.common_multiple(1)), (-8, 1));
+        pack_rounds(&mut shrinking, 1, 20);
+        assert_eq!(shrinking.monkeys.iter().map(|monkey| monkey.items.len()).sum::<usize>(), 10);
+    }
+
+    #[test]
+    fn cycle_test() {
+        let pack = input_to_pack(INPUT);
+        assert_eq!(pack.predicted_monkey_business(3, 20), 10605);
+        assert_eq!(pack.predicted_monkey_business(1, 10000), 2713310158);
+
+        let mut simulated = input_to_pack(INPUT);
+        pack_rounds(&mut simulated, 1, 1234);
+        let simulated_counts: Vec<u64> = simulated.monkeys.iter().map(|monkey| monkey.inspection_counter as u64).collect();
+        assert_eq!(pack.predicted_inspection_counts(1, 1234), simulated_counts);
+
+        // Every item trajectory in the sample settles within 200 rounds into a
+        // cycle of 171 or 448 rounds, so the pack repeats every 76608 rounds.
+        let period = 171 * 448;
+        let rounds = 1_000_000_000_000;
+        let offset = 200 + (rounds - 200) % period;
+        let mut simulated = input_to_pack(INPUT);
+        pack_rounds(&mut simulated, 1, offset as usize);
+        let before: Vec<u64> = simulated.monkeys.iter().map(|monkey| monkey.inspection_counter as u64).collect();
+        pack_rounds(&mut simulated, 1, period as usize);
+        let after: Vec<u64> = simulated.monkeys.iter().map(|monkey| monkey.inspection_counter as u64).collect();
+
+        let cycles = (rounds - offset) / period;
+        let expected: Vec<u64> = before.iter().zip(&after).map(|(before, after)| before + cycles * (after - before)).collect();
+        assert_eq!(pack.predicted_inspection_counts(1, rounds), expected);
+    }
+
+    #[test]
+    fn statistics_test() {
+        let mut pack = input_to_pack(INPUT);
+        let statistics = pack_rounds_with_statistics(&mut pack, 3, 20);
+        assert_eq!(statistics.rounds().len(), 20);
+        assert_eq!(statistics.total_inspections(), [101, 95, 7, 105]);
+        assert_eq!(statistics.rounds()[0].inspections(), [2, 4, 3, 5]);
+        assert_eq!(statistics.rounds()[0].queue_lengths(), [2, 4, 3, 1]);
+        assert_eq!(statistics.rounds()[0].transfers()[0][3], 2);
+
+        let rounds_csv = statistics.rounds_csv();
+        let mut lines = rounds_csv.lines();
+        assert_eq!(lines.next(), Some("round,monkey,queue_length,inspections,items_thrown"));
+        assert_eq!(lines.next(), Some("1,0,2,2,2"));
+        assert_eq!(rounds_csv.lines().count(), 1 + 20 * 4);
+
+        let transfers_csv = statistics.transfers_csv();
+        assert_eq!(transfers_csv.lines().next(), Some("round,from,to,items"));
+        assert!(transfers_csv.lines().any(|line| line == "1,0,3,2"));
+
+        let flow_graph = statistics.flow_graph();
+        assert!(flow_graph.starts_with("digraph pack {"));
+        assert!(flow_graph.contains("monkey3 [label=\"Monkey 3\\n105 inspections\"];"));
+        assert!(flow_graph.contains("monkey0 -> monkey3"));
+    }
 }
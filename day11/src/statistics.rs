@@ -0,0 +1,126 @@
+use std::collections::VecDeque;
+
+use super::WorryLevel;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundStatistics {
+    queue_lengths: Vec<usize>,
+    inspections: Vec<usize>,
+    transfers: Vec<Vec<usize>>,
+}
+
+impl RoundStatistics {
+    pub fn new(queue_lengths: Vec<usize>) -> Self {
+        let monkey_count = queue_lengths.len();
+        Self { queue_lengths, inspections: vec![0; monkey_count], transfers: vec![vec![0; monkey_count]; monkey_count] }
+    }
+
+    pub fn record_turn(&mut self, monkey: usize, inspected_list: &VecDeque<(WorryLevel, usize)>) {
+        self.inspections[monkey] += inspected_list.len();
+        inspected_list.iter().for_each(|&(_, target)| self.transfers[monkey][target] += 1);
+    }
+
+    pub fn queue_lengths(&self) -> &[usize] {
+        &self.queue_lengths
+    }
+
+    pub fn inspections(&self) -> &[usize] {
+        &self.inspections
+    }
+
+    pub fn transfers(&self) -> &[Vec<usize>] {
+        &self.transfers
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PackStatistics {
+    rounds: Vec<RoundStatistics>,
+}
+
+impl PackStatistics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, round: RoundStatistics) {
+        self.rounds.push(round);
+    }
+
+    pub fn rounds(&self) -> &[RoundStatistics] {
+        &self.rounds
+    }
+
+    pub fn total_inspections(&self) -> Vec<usize> {
+        let mut totals = vec![0; self.monkey_count()];
+        self.rounds().iter().for_each(|round| {
+            round.inspections().iter().enumerate().for_each(|(monkey, count)| totals[monkey] += count);
+        });
+
+        totals
+    }
+
+    pub fn total_transfers(&self) -> Vec<Vec<usize>> {
+        let mut totals = vec![vec![0; self.monkey_count()]; self.monkey_count()];
+        self.rounds().iter().for_each(|round| {
+            for (from, targets) in round.transfers().iter().enumerate() {
+                targets.iter().enumerate().for_each(|(to, count)| totals[from][to] += count);
+            }
+        });
+
+        totals
+    }
+
+    pub fn rounds_csv(&self) -> String {
+        let mut csv = String::from("round,monkey,queue_length,inspections,items_thrown\n");
+
+        for (round_index, round) in self.rounds().iter().enumerate() {
+            for monkey in 0..round.queue_lengths().len() {
+                let items_thrown: usize = round.transfers()[monkey].iter().sum();
+                csv += &format!("{},{},{},{},{}\n", round_index + 1, monkey, round.queue_lengths()[monkey], round.inspections()[monkey], items_thrown);
+            }
+        }
+
+        csv
+    }
+
+    pub fn transfers_csv(&self) -> String {
+        let mut csv = String::from("round,from,to,items\n");
+
+        for (round_index, round) in self.rounds().iter().enumerate() {
+            for (from, targets) in round.transfers().iter().enumerate() {
+                for (to, &count) in targets.iter().enumerate().filter(|(_, &count)| count > 0) {
+                    csv += &format!("{},{},{},{}\n", round_index + 1, from, to, count);
+                }
+            }
+        }
+
+        csv
+    }
+
+    pub fn flow_graph(&self) -> String {
+        let inspections = self.total_inspections();
+        let transfers = self.total_transfers();
+        let max_transfer = transfers.iter().flatten().copied().max().unwrap_or(0).max(1);
+
+        let mut graph = String::from("digraph pack {\n");
+
+        for (monkey, count) in inspections.iter().enumerate() {
+            graph += &format!("    monkey{monkey} [label=\"Monkey {monkey}\\n{count} inspections\"];\n");
+        }
+
+        for (from, targets) in transfers.iter().enumerate() {
+            for (to, &count) in targets.iter().enumerate().filter(|(_, &count)| count > 0) {
+                let pen_width = 1.0 + 4.0 * count as f64 / max_transfer as f64;
+                graph += &format!("    monkey{from} -> monkey{to} [label=\"{count}\", penwidth={pen_width:.2}];\n");
+            }
+        }
+
+        graph += "}\n";
+        graph
+    }
+
+    fn monkey_count(&self) -> usize {
+        self.rounds().first().map(|round| round.queue_lengths().len()).unwrap_or(0)
+    }
+}
@@ -0,0 +1,70 @@
+use std::{io::{self, BufRead}, fmt::Display};
+
+use super::*;
+use super::parser::ParseError;
+
+pub struct DividerRanks<'a> {
+    divider_packets: &'a (NestedList, NestedList),
+    smaller_counts: (usize, usize),
+}
+
+impl<'a> DividerRanks<'a> {
+    pub fn new(divider_packets: &'a (NestedList, NestedList)) -> Self {
+        Self { divider_packets, smaller_counts: (0, 0) }
+    }
+
+    pub fn count(&mut self, packet: &NestedList) {
+        if *packet < self.divider_packets.0 {
+            self.smaller_counts.0 += 1;
+        }
+        if *packet < self.divider_packets.1 {
+            self.smaller_counts.1 += 1;
+        }
+    }
+
+    pub fn decoder_key(&self) -> usize {
+        let (first, second) = self.divider_packets;
+        let first_index = self.smaller_counts.0 + usize::from(second < first) + 1;
+        let second_index = self.smaller_counts.1 + usize::from(first < second) + 1;
+
+        first_index * second_index
+    }
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse { line: usize, error: ParseError },
+}
+
+impl Display for StreamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::Io(error) => write!(f, "{error}"),
+            StreamError::Parse { line, error } => write!(f, "line {line}: {error}"),
+        }
+    }
+}
+
+impl From<io::Error> for StreamError {
+    fn from(error: io::Error) -> Self {
+        StreamError::Io(error)
+    }
+}
+
+pub fn stream_decoder_key(mut reader: impl BufRead, divider_packets: &(NestedList, NestedList)) -> Result<usize, StreamError> {
+    let mut ranks = DividerRanks::new(divider_packets);
+    let mut line = String::new();
+    let mut line_number = 0;
+
+    while reader.read_line(&mut line)? > 0 {
+        line_number += 1;
+        if !line.trim().is_empty() {
+            let packet: NestedList = line.parse().map_err(|error| StreamError::Parse { line: line_number, error })?;
+            ranks.count(&packet);
+        }
+        line.clear();
+    }
+
+    Ok(ranks.decoder_key())
+}
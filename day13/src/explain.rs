@@ -0,0 +1,167 @@
+use std::{cmp::Ordering, fmt::Write, slice};
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Side::Left => write!(f, "left"),
+            Side::Right => write!(f, "right"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    Compare { index: Option<usize>, left: String, right: String },
+    Promote { side: Side, number: i64 },
+    Smaller(Side),
+    RanOut(Side),
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Step::Compare { left, right, .. } => write!(f, "Compare {left} vs {right}"),
+            Step::Promote { side, number } => write!(f, "Mixed types; convert {side} to [{number}] and retry comparison"),
+            Step::Smaller(Side::Left) => write!(f, "Left side is smaller, so inputs are in the right order"),
+            Step::Smaller(Side::Right) => write!(f, "Right side is smaller, so inputs are not in the right order"),
+            Step::RanOut(Side::Left) => write!(f, "Left side ran out of items, so inputs are in the right order"),
+            Step::RanOut(Side::Right) => write!(f, "Right side ran out of items, so inputs are not in the right order"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderExplanation {
+    steps: Vec<(usize, Step)>,
+    ordering: Ordering,
+}
+
+impl OrderExplanation {
+    pub fn ordering(&self) -> Ordering {
+        self.ordering
+    }
+
+    pub fn steps(&self) -> &[(usize, Step)] {
+        &self.steps
+    }
+}
+
+impl Display for OrderExplanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (depth, step) in &self.steps {
+            writeln!(f, "{}- {step}", "  ".repeat(*depth))?;
+        }
+        Ok(())
+    }
+}
+
+impl ListPair {
+    pub fn explain_order(&self) -> OrderExplanation {
+        explain_order(&self.left, &self.right)
+    }
+}
+
+const SUMMARY_LENGTH: usize = 64;
+
+struct Capped {
+    text: String,
+}
+
+impl Write for Capped {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        let remaining = SUMMARY_LENGTH + 1 - self.text.len();
+        if s.len() > remaining {
+            self.text.push_str(&s[..remaining]);
+            Err(std::fmt::Error)
+        } else {
+            self.text.push_str(s);
+            Ok(())
+        }
+    }
+}
+
+// Steps only keep a length-capped rendering of each side, so explaining deep
+// packets costs memory linear in the number of steps.
+fn summarize(packet: &impl Display) -> String {
+    let mut capped = Capped { text: String::new() };
+    if write!(capped, "{packet}").is_err() {
+        capped.text.truncate(SUMMARY_LENGTH - 3);
+        capped.text.push_str("...");
+    }
+
+    capped.text
+}
+
+struct Frame<'a> {
+    left: &'a [ListItem],
+    right: &'a [ListItem],
+    next: usize,
+    depth: usize,
+}
+
+pub fn explain_order(left: &NestedList, right: &NestedList) -> OrderExplanation {
+    let mut steps = vec![(0, Step::Compare { index: None, left: summarize(left), right: summarize(right) })];
+    let ordering = explain_lists(Frame { left: &left.list, right: &right.list, next: 0, depth: 0 }, &mut steps);
+
+    OrderExplanation { steps, ordering }
+}
+
+fn explain_lists(outermost: Frame, steps: &mut Vec<(usize, Step)>) -> Ordering {
+    let mut stack = vec![outermost];
+
+    while let Some(frame) = stack.pop() {
+        let depth = frame.depth + 1;
+        let (left, right) = match (frame.left.get(frame.next), frame.right.get(frame.next)) {
+            (Some(left), Some(right)) => (left, right),
+            (None, Some(_)) => {
+                steps.push((depth, Step::RanOut(Side::Left)));
+                return Ordering::Less;
+            },
+            (Some(_), None) => {
+                steps.push((depth, Step::RanOut(Side::Right)));
+                return Ordering::Greater;
+            },
+            (None, None) => continue,
+        };
+        let index = Some(frame.next);
+        stack.push(Frame { next: frame.next + 1, ..frame });
+        steps.push((depth, Step::Compare { index, left: summarize(left), right: summarize(right) }));
+
+        match (left, right) {
+            (ListItem::Number(left_num), ListItem::Number(right_num)) => match left_num.cmp(right_num) {
+                Ordering::Less => {
+                    steps.push((depth + 1, Step::Smaller(Side::Left)));
+                    return Ordering::Less;
+                },
+                Ordering::Greater => {
+                    steps.push((depth + 1, Step::Smaller(Side::Right)));
+                    return Ordering::Greater;
+                },
+                Ordering::Equal => {},
+            },
+            (ListItem::NestedList(left_list), ListItem::NestedList(right_list)) => {
+                stack.push(Frame { left: &left_list.list, right: &right_list.list, next: 0, depth });
+            },
+            (ListItem::NestedList(left_list), ListItem::Number(number)) => {
+                steps.push((depth + 1, Step::Promote { side: Side::Right, number: *number }));
+                steps.push((depth + 1, Step::Compare { index, left: summarize(left), right: format!("[{number}]") }));
+                stack.push(Frame { left: &left_list.list, right: slice::from_ref(right), next: 0, depth: depth + 1 });
+            },
+            (ListItem::Number(number), ListItem::NestedList(right_list)) => {
+                steps.push((depth + 1, Step::Promote { side: Side::Left, number: *number }));
+                steps.push((depth + 1, Step::Compare { index, left: format!("[{number}]"), right: summarize(right) }));
+                stack.push(Frame { left: slice::from_ref(left), right: &right_list.list, next: 0, depth: depth + 1 });
+            },
+        }
+    }
+
+    Ordering::Equal
+}
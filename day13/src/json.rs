@@ -0,0 +1,96 @@
+use serde_json::Value;
+
+use super::*;
+
+/// Dropping a `Value` recurses once per nesting level, so packets nested
+/// deeper than this are rejected instead of converted. The limit is the
+/// deepest nesting `serde_json` accepts when parsing.
+pub const MAX_JSON_DEPTH: usize = 127;
+
+impl TryFrom<&NestedList> for Value {
+    type Error = ();
+
+    fn try_from(list: &NestedList) -> Result<Self, Self::Error> {
+        let mut stack = vec![(list.list.as_slice(), 0, Vec::with_capacity(list.list.len()))];
+
+        loop {
+            let (items, index, values) = stack.last_mut().unwrap();
+            match items.get(*index) {
+                Some(ListItem::Number(number)) => {
+                    *index += 1;
+                    values.push(Value::from(*number));
+                },
+                Some(ListItem::NestedList(nested)) => {
+                    *index += 1;
+                    if stack.len() == MAX_JSON_DEPTH {
+                        return Err(());
+                    }
+                    stack.push((nested.list.as_slice(), 0, Vec::with_capacity(nested.list.len())));
+                },
+                None => {
+                    let (_, _, values) = stack.pop().unwrap();
+                    match stack.last_mut() {
+                        Some((_, _, parent)) => parent.push(Value::Array(values)),
+                        None => return Ok(Value::Array(values)),
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl TryFrom<&ListItem> for Value {
+    type Error = ();
+
+    fn try_from(item: &ListItem) -> Result<Self, Self::Error> {
+        match item {
+            ListItem::Number(number) => Ok(Value::from(*number)),
+            ListItem::NestedList(list) => Value::try_from(list),
+        }
+    }
+}
+
+impl TryFrom<&Value> for NestedList {
+    type Error = ();
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let Value::Array(values) = value else {
+            return Err(());
+        };
+        let mut stack = vec![(values.as_slice(), 0, Vec::with_capacity(values.len()))];
+
+        loop {
+            let (values, index, items) = stack.last_mut().unwrap();
+            match values.get(*index) {
+                Some(Value::Number(number)) => {
+                    *index += 1;
+                    items.push(ListItem::Number(number.as_i64().ok_or(())?));
+                },
+                Some(Value::Array(nested)) => {
+                    *index += 1;
+                    stack.push((nested.as_slice(), 0, Vec::with_capacity(nested.len())));
+                },
+                Some(_) => return Err(()),
+                None => {
+                    let (_, _, list) = stack.pop().unwrap();
+                    match stack.last_mut() {
+                        Some((_, _, parent)) => parent.push(ListItem::NestedList(NestedList { list })),
+                        None => return Ok(NestedList { list }),
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl TryFrom<&Value> for ListItem {
+    type Error = ();
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Number(number) => number.as_i64().map(ListItem::Number).ok_or(()),
+            Value::Array(_) => Ok(ListItem::NestedList(value.try_into()?)),
+            _ => Err(()),
+        }
+    }
+}
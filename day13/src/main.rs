@@ -1,8 +1,19 @@
-use std::{fs, path::Path, str::FromStr};
+use std::{fs, io::BufReader, path::Path, str::FromStr, fmt::Display, cmp::Ordering, slice};
+
+use decoder::*;
+
+mod decoder;
+mod explain;
+mod json;
+mod parser;
 
 fn main() {
     part_one();
     part_two();
+
+    if let Some(pair_number) = std::env::args().nth(1) {
+        explain_pair(pair_number.parse().expect("pair number expected"));
+    }
 }
 
 fn read_input() -> String {
@@ -11,7 +22,7 @@ fn read_input() -> String {
 }
 
 fn input_to_pairs_of_lists(input: &str) -> Vec<ListPair> {
-    input.split("\n\n").map(|block| block.parse()).flatten().collect()
+    input.split("\n\n").filter(|block| !block.trim().is_empty()).map(|block| block.parse().unwrap()).collect()
 }
 
 fn part_one() {
@@ -23,13 +34,27 @@ fn part_one() {
 }
 
 fn part_two() {
+    let file = fs::File::open(Path::new("res/input")).expect("input file needed");
+    let divider_packets = (NestedList::new().with_list(NestedList::new().with_number(2)),
+                           NestedList::new().with_list(NestedList::new().with_number(6)));
+    let decoder_key = stream_decoder_key(BufReader::new(file), &divider_packets).unwrap_or_else(|error| panic!("{error}"));
+
+    println!("Decoder key: {decoder_key}");
+}
+
+fn explain_pair(pair_number: usize) {
     let input = read_input();
     let pairs = input_to_pairs_of_lists(&input);
-    let divider_packets = (NestedList { list: vec![ListItem::NestedList(NestedList { list: vec![ListItem::Number(2)] })]},
-                           NestedList { list: vec![ListItem::NestedList(NestedList { list: vec![ListItem::Number(6)] })]});
-    let decoder_key = calculate_decoder_key(&pairs, &divider_packets);
 
-    println!("Decoder key: {decoder_key}");
+    let explanation = pairs[pair_number - 1].explain_order();
+    let verdict = match explanation.ordering() {
+        Ordering::Less => "in the right order",
+        _ => "not in the right order",
+    };
+
+    println!("== Pair {pair_number} ==");
+    print!("{explanation}");
+    println!("Pair {pair_number} is {verdict} after {} steps", explanation.steps().len());
 }
 
 fn calculate_correct_order_index_sum(pairs: &Vec<ListPair>) -> usize {
@@ -42,24 +67,6 @@ fn calculate_correct_order_index_sum(pairs: &Vec<ListPair>) -> usize {
     }).flatten().sum()
 }
 
-fn total_packet_list_with_dividers(pairs: &Vec<ListPair>, divider_packets: &(NestedList, NestedList)) -> Vec<NestedList> {
-    let mut packets: Vec<NestedList> = pairs.iter().flat_map(|pair| [pair.left.clone(), pair.right.clone()]).collect();
-    packets.push(divider_packets.0.clone());
-    packets.push(divider_packets.1.clone());
-
-    packets
-}
-
-fn calculate_decoder_key(pairs: &Vec<ListPair>, divider_packets: &(NestedList, NestedList)) -> usize {
-    let mut packets = total_packet_list_with_dividers(&pairs, &divider_packets);
-    packets.sort();
-
-    let first_index = packets.iter().position(|packet| *packet == divider_packets.0).unwrap() + 1;
-    let second_index = packets.iter().position(|packet| *packet == divider_packets.1).unwrap() + 1;
-
-    first_index * second_index
-}
-
 #[derive(Debug, PartialEq)]
 struct ListPair {
     left: NestedList,
@@ -72,127 +79,215 @@ impl ListPair {
     }
 }
 
-impl FromStr for ListPair {
-    type Err = ();
+#[derive(Default)]
+struct NestedList {
+    list: Vec<ListItem>,
+}
+
+impl NestedList {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines: Vec<&str> = s.split("\n").filter(|line| !line.is_empty()).collect();
-        if lines.len() != 2 {
-            Err(())
-        } else {
-            let left = lines[0].parse();
-            let right = lines[1].parse();
+    pub fn with_item(mut self, item: impl Into<ListItem>) -> Self {
+        self.list.push(item.into());
+        self
+    }
 
-            if left.is_ok() && right.is_ok() {
-                Ok(ListPair { left: left.unwrap(), right: right.unwrap() })
-            } else {
-                Err(())
+    pub fn with_number(self, number: i64) -> Self {
+        self.with_item(number)
+    }
+
+    pub fn with_list(self, list: NestedList) -> Self {
+        self.with_item(list)
+    }
+}
+
+impl Drop for NestedList {
+    fn drop(&mut self) {
+        let mut items = std::mem::take(&mut self.list);
+        while let Some(item) = items.pop() {
+            if let ListItem::NestedList(mut nested) = item {
+                items.append(&mut nested.list);
             }
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-struct NestedList {
-    list: Vec<ListItem>,
+impl Clone for NestedList {
+    fn clone(&self) -> Self {
+        let mut stack = vec![(self.list.as_slice(), 0, Vec::with_capacity(self.list.len()))];
+
+        loop {
+            let (items, index, cloned) = stack.last_mut().unwrap();
+            match items.get(*index) {
+                Some(ListItem::Number(number)) => {
+                    *index += 1;
+                    cloned.push(ListItem::Number(*number));
+                },
+                Some(ListItem::NestedList(nested)) => {
+                    *index += 1;
+                    stack.push((nested.list.as_slice(), 0, Vec::with_capacity(nested.list.len())));
+                },
+                None => {
+                    let (_, _, list) = stack.pop().unwrap();
+                    match stack.last_mut() {
+                        Some((_, _, parent)) => parent.push(ListItem::NestedList(NestedList { list })),
+                        None => return NestedList { list },
+                    }
+                },
+            }
+        }
+    }
 }
 
-impl FromStr for NestedList {
-    type Err = ();
+impl PartialEq for NestedList {
+    fn eq(&self, other: &Self) -> bool {
+        items_equal(&self.list, &other.list)
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with("[") && s.ends_with("]") {
-            let truncated = &s[1..s.len()-1];
+impl Eq for NestedList {}
 
-            if truncated.len() == 0 {
-                return Ok(NestedList { list: Vec::new() })
-            }
+impl Ord for NestedList {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_items(&self.list, &other.list)
+    }
+}
 
-            let parts: Vec<&str> = truncated.split(",").collect();
-            let mut combined_elements = Vec::new();
-            let mut nest_start = 0;
-            let mut nest_level: i64 = 0;
-
-
-            for i in 0..parts.len() {
-                let part = parts[i];
-                let new_nest = part.matches("[").count() as i64 - part.matches("]").count() as i64;
-                if nest_level == 0 {
-                    nest_start = i;
-                }
-                nest_level += new_nest;
-                if new_nest > 0 {
-                    if nest_level == 0 {
-                        nest_start = i;
-                    }
-                } else if new_nest < 0 {
-                    if nest_level == 0 {
-                        let combined = parts[nest_start..=i].join(",");
-                        combined_elements.push(combined);
-                    }
-                } else {
-                    if nest_level == 0 {
-                        combined_elements.push(part.to_owned());
-                    }
-                }
-            }
+impl PartialOrd for NestedList {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-            let parsed: Result<Vec<ListItem>, _> = combined_elements.iter().map(|element| element.parse()).collect();
+impl std::fmt::Debug for NestedList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Display for NestedList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut stack = vec![(self.list.as_slice(), 0)];
+        write!(f, "[")?;
 
-            match parsed {
-                Ok(list) => Ok(NestedList { list }),
-                Err(_) => Err(()),
+        while let Some((items, index)) = stack.pop() {
+            match items.get(index) {
+                Some(item) => {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    stack.push((items, index + 1));
+                    match item {
+                        ListItem::Number(number) => write!(f, "{number}")?,
+                        ListItem::NestedList(nested) => {
+                            write!(f, "[")?;
+                            stack.push((nested.list.as_slice(), 0));
+                        },
+                    }
+                },
+                None => write!(f, "]")?,
             }
-        } else {
-            Err(())
         }
+
+        Ok(())
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum ListItem {
-    Number(u64),
+    Number(i64),
     NestedList(NestedList),
 }
 
-impl FromStr for ListItem {
-    type Err = ();
+impl From<i64> for ListItem {
+    fn from(number: i64) -> Self {
+        ListItem::Number(number)
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with('[') {
-            match s.parse() {
-                Ok(list) => Ok(ListItem::NestedList(list)),
-                Err(_) => Err(()),
-            }
-        } else {
-            match s.parse() {
-                Ok(num) => Ok(ListItem::Number(num)),
-                Err(_) => Err(()),
-            }
+impl From<NestedList> for ListItem {
+    fn from(list: NestedList) -> Self {
+        ListItem::NestedList(list)
+    }
+}
+
+impl Display for ListItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListItem::Number(number) => write!(f, "{number}"),
+            ListItem::NestedList(list) => write!(f, "{list}"),
         }
     }
 }
 
 impl Ord for ListItem {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match (self, other) {
-            (Self::NestedList(self_list), Self::NestedList(other_list)) => self_list.cmp(other_list),
-            (Self::NestedList(self_list), Self::Number(other_num)) => self_list.cmp(&NestedList { list: vec![ListItem::Number(*other_num)] }),
-            (Self::Number(self_num), Self::NestedList(other_list)) => NestedList { list: vec![ListItem::Number(*self_num)] }.cmp(other_list),
-            (Self::Number(self_num), Self::Number(other_num)) => self_num.cmp(other_num),
-        }
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_items(slice::from_ref(self), slice::from_ref(other))
     }
 }
 
 impl PartialOrd for ListItem {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
+// Packets can nest far deeper than the call stack allows, so comparisons walk
+// both sides with an explicit stack instead of recursing.
+fn items_equal(left: &[ListItem], right: &[ListItem]) -> bool {
+    let mut stack = vec![(left, right)];
+
+    while let Some((left, right)) = stack.pop() {
+        if left.len() != right.len() {
+            return false;
+        }
+        for (left_item, right_item) in left.iter().zip(right) {
+            match (left_item, right_item) {
+                (ListItem::Number(left_num), ListItem::Number(right_num)) if left_num == right_num => {},
+                (ListItem::NestedList(left_list), ListItem::NestedList(right_list)) => stack.push((&left_list.list, &right_list.list)),
+                _ => return false,
+            }
+        }
+    }
+
+    true
+}
+
+fn compare_items(left: &[ListItem], right: &[ListItem]) -> Ordering {
+    let mut stack = vec![(left, right, 0)];
+
+    while let Some((left, right, index)) = stack.pop() {
+        let (left_item, right_item) = match (left.get(index), right.get(index)) {
+            (Some(left_item), Some(right_item)) => (left_item, right_item),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (None, None) => continue,
+        };
+        stack.push((left, right, index + 1));
+
+        match (left_item, right_item) {
+            (ListItem::Number(left_num), ListItem::Number(right_num)) => match left_num.cmp(right_num) {
+                Ordering::Equal => {},
+                ordering => return ordering,
+            },
+            (ListItem::NestedList(left_list), ListItem::NestedList(right_list)) => stack.push((&left_list.list, &right_list.list, 0)),
+            (ListItem::NestedList(left_list), ListItem::Number(_)) => stack.push((&left_list.list, slice::from_ref(right_item), 0)),
+            (ListItem::Number(_), ListItem::NestedList(right_list)) => stack.push((slice::from_ref(left_item), &right_list.list, 0)),
+        }
+    }
+
+    Ordering::Equal
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::parser::*;
+    use super::explain::*;
+    use super::json::*;
+    use std::cmp::Ordering;
 
     const INPUT: &str = r#"[1,1,3,1,1]
 [1,1,5,1,1]
@@ -291,8 +386,137 @@ mod tests {
         let pairs = input_to_pairs_of_lists(INPUT);
         let divider_packets = (NestedList { list: vec![ListItem::NestedList(NestedList { list: vec![ListItem::Number(2)] })]},
                                NestedList { list: vec![ListItem::NestedList(NestedList { list: vec![ListItem::Number(6)] })]});
-        let decoder_key = calculate_decoder_key(&pairs, &divider_packets);
+        let mut ranks = DividerRanks::new(&divider_packets);
+        pairs.iter().for_each(|pair| {
+            ranks.count(&pair.left);
+            ranks.count(&pair.right);
+        });
+
+        assert_eq!(ranks.decoder_key(), 140);
+    }
 
-        assert_eq!(decoder_key, 140);
+    #[test]
+    fn serialize_test() {
+        for line in INPUT.split("\n").filter(|line| !line.is_empty()) {
+            let packet: NestedList = line.parse().unwrap();
+            assert_eq!(packet.to_string(), line);
+
+            let value = serde_json::Value::try_from(&packet).unwrap();
+            assert_eq!(value, serde_json::from_str::<serde_json::Value>(line).unwrap());
+            assert_eq!(NestedList::try_from(&value), Ok(packet));
+        }
+
+        let built = NestedList::new()
+            .with_number(1)
+            .with_list(NestedList::new().with_number(2).with_list(NestedList::new()))
+            .with_item(10);
+        assert_eq!(built.to_string(), "[1,[2,[]],10]");
+        assert_eq!(built, "[1,[2,[]],10]".parse().unwrap());
+
+        assert!(NestedList::try_from(&serde_json::json!([1, "two"])).is_err());
+        assert!(NestedList::try_from(&serde_json::json!(3)).is_err());
+        assert!(ListItem::try_from(&serde_json::json!(1.5)).is_err());
+        assert_eq!(format!("{:?}", ListItem::NestedList(built)), "NestedList([1,[2,[]],10])");
+
+        let depth = 100_000;
+        let deep: NestedList = ("[".repeat(depth) + "7" + &"]".repeat(depth)).parse().unwrap();
+        assert!(serde_json::Value::try_from(&deep).is_err());
+        assert!(serde_json::Value::try_from(&ListItem::NestedList(deep.clone())).is_err());
+        assert_eq!(format!("{deep:?}").len(), 2 * depth + 1);
+
+        let deepest = "[".repeat(MAX_JSON_DEPTH) + "7" + &"]".repeat(MAX_JSON_DEPTH);
+        let packet: NestedList = deepest.parse().unwrap();
+        let value = serde_json::Value::try_from(&packet).unwrap();
+        assert_eq!(value, serde_json::from_str::<serde_json::Value>(&deepest).unwrap());
+        assert_eq!(NestedList::try_from(&value), Ok(packet.clone()));
+        assert!(serde_json::Value::try_from(&NestedList::new().with_list(packet)).is_err());
+    }
+
+    #[test]
+    fn parse_error_test() {
+        assert_eq!(" [ -12 , [3,[ ]], 40 ]\n".parse::<NestedList>().unwrap().to_string(), "[-12,[3,[]],40]");
+        assert_eq!("7".parse::<ListItem>(), Ok(ListItem::Number(7)));
+
+        let error = "[1,[2,x]]".parse::<NestedList>().unwrap_err();
+        assert_eq!(error.offset(), 6);
+        assert_eq!(error.kind(), ParseErrorKind::UnexpectedCharacter('x'));
+        assert_eq!(error.to_string(), "unexpected character 'x' at byte 6");
+
+        assert_eq!("[1,,2]".parse::<NestedList>().unwrap_err().offset(), 3);
+        assert_eq!("[1,2]]".parse::<NestedList>().unwrap_err().offset(), 5);
+        assert_eq!("[1,[2]".parse::<NestedList>().unwrap_err().kind(), ParseErrorKind::UnexpectedEnd);
+        assert_eq!("[1,-]".parse::<NestedList>().unwrap_err().kind(), ParseErrorKind::InvalidNumber);
+        assert_eq!("  5".parse::<NestedList>().unwrap_err(), ParseError::new(2, ParseErrorKind::ExpectedList));
+        assert_eq!("[1]\n[2,]".parse::<ListPair>().unwrap_err().offset(), 7);
+        assert_eq!("[1]\n[2]\n[3]".parse::<ListPair>().unwrap_err(), ParseError::new(8, ParseErrorKind::ExpectedPair));
+
+        let depth = 100_000;
+        let deep = "[".repeat(depth) + &"]".repeat(depth);
+        let packet: NestedList = deep.parse().unwrap();
+        let copy = packet.clone();
+        assert_eq!(copy, packet);
+        assert_eq!(copy.cmp(&packet), Ordering::Equal);
+        assert_eq!(packet.to_string(), deep);
+
+        let deeper: NestedList = ("[".repeat(depth + 1) + &"]".repeat(depth + 1)).parse().unwrap();
+        assert!(packet < deeper);
+        assert_ne!(packet, deeper);
+        assert!(ListItem::Number(1) > ListItem::NestedList(deeper));
+    }
+
+    #[test]
+    fn explain_test() {
+        let pairs = input_to_pairs_of_lists(INPUT);
+
+        for pair in &pairs {
+            assert_eq!(pair.explain_order().ordering(), pair.left.cmp(&pair.right));
+        }
+
+        assert_eq!(pairs[1].explain_order().to_string(), r#"- Compare [[1],[2,3,4]] vs [[1],4]
+  - Compare [1] vs [1]
+    - Compare 1 vs 1
+  - Compare [2,3,4] vs 4
+    - Mixed types; convert right to [4] and retry comparison
+    - Compare [2,3,4] vs [4]
+      - Compare 2 vs 4
+        - Left side is smaller, so inputs are in the right order
+"#);
+        assert_eq!(pairs[6].explain_order().to_string(), r#"- Compare [[[]]] vs [[]]
+  - Compare [[]] vs []
+    - Right side ran out of items, so inputs are not in the right order
+"#);
+
+        let explanation = pairs[2].explain_order();
+        assert_eq!(explanation.ordering(), Ordering::Greater);
+        assert_eq!(explanation.steps()[1].1, Step::Compare { index: Some(0), left: "9".to_owned(), right: "[8,7,6]".to_owned() });
+        assert_eq!(explanation.steps()[2], (2, Step::Promote { side: Side::Left, number: 9 }));
+        assert_eq!(explanation.steps().last(), Some(&(4, Step::Smaller(Side::Right))));
+
+        let depth = 100_000;
+        let deep_pair: ListPair = format!("{}1{}\n{}2{}", "[".repeat(depth), "]".repeat(depth), "[".repeat(depth), "]".repeat(depth)).parse().unwrap();
+        let explanation = deep_pair.explain_order();
+        assert_eq!(explanation.ordering(), Ordering::Less);
+        assert_eq!(explanation.steps().len(), depth + 2);
+        assert_eq!(explanation.steps()[0].1, Step::Compare { index: None, left: "[".repeat(61) + "...", right: "[".repeat(61) + "..." });
+        assert_eq!(explanation.steps()[depth - 1], (depth - 1, Step::Compare { index: Some(0), left: "[1]".to_owned(), right: "[2]".to_owned() }));
+        assert_eq!(explanation.steps().last(), Some(&(depth + 1, Step::Smaller(Side::Left))));
+    }
+
+    #[test]
+    fn streaming_decoder_key_test() {
+        let divider_packets = (NestedList::new().with_list(NestedList::new().with_number(2)),
+                               NestedList::new().with_list(NestedList::new().with_number(6)));
+        assert_eq!(stream_decoder_key(INPUT.as_bytes(), &divider_packets).unwrap(), 140);
+
+        let swapped_dividers = (divider_packets.1.clone(), divider_packets.0.clone());
+        assert_eq!(stream_decoder_key(INPUT.as_bytes(), &swapped_dividers).unwrap(), 140);
+
+        match stream_decoder_key("[1]\n\n[2,[3]\n".as_bytes(), &divider_packets) {
+            Err(StreamError::Parse { line, error }) => {
+                assert_eq!(line, 3);
+                assert_eq!(error.kind(), ParseErrorKind::UnexpectedEnd);
+            },
+            other => panic!("unexpected result {other:?}"),
+        }
     }
 }
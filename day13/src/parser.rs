@@ -0,0 +1,203 @@
+use std::{fmt::Display, iter::Peekable, str::CharIndices};
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnexpectedCharacter(char),
+    UnexpectedEnd,
+    InvalidNumber,
+    ExpectedList,
+    ExpectedPair,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    offset: usize,
+    kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub fn new(offset: usize, kind: ParseErrorKind) -> Self {
+        Self { offset, kind }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    fn shifted(self, by: usize) -> Self {
+        Self { offset: self.offset + by, kind: self.kind }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind() {
+            ParseErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character '{c}' at byte {}", self.offset()),
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input at byte {}", self.offset()),
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number at byte {}", self.offset()),
+            ParseErrorKind::ExpectedList => write!(f, "expected a list at byte {}", self.offset()),
+            ParseErrorKind::ExpectedPair => write!(f, "expected two packets at byte {}", self.offset()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Comma,
+    Number(i64),
+}
+
+struct Tokenizer<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, chars: input.char_indices().peekable() }
+    }
+
+    fn number(&mut self, start: usize) -> Result<Token, ParseError> {
+        let mut end = start + 1;
+        while let Some(&(offset, c)) = self.chars.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            end = offset + 1;
+            self.chars.next();
+        }
+
+        match self.input[start..end].parse() {
+            Ok(number) => Ok(Token::Number(number)),
+            Err(_) => Err(ParseError::new(start, ParseErrorKind::InvalidNumber)),
+        }
+    }
+}
+
+impl Iterator for Tokenizer<'_> {
+    type Item = Result<(usize, Token), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&(offset, c)) = self.chars.peek() {
+            if !c.is_whitespace() {
+                self.chars.next();
+                let token = match c {
+                    '[' => Ok(Token::Open),
+                    ']' => Ok(Token::Close),
+                    ',' => Ok(Token::Comma),
+                    '-' | '0'..='9' => self.number(offset),
+                    _ => Err(ParseError::new(offset, ParseErrorKind::UnexpectedCharacter(c))),
+                };
+                return Some(token.map(|token| (offset, token)));
+            }
+            self.chars.next();
+        }
+
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Expect {
+    Item,
+    ItemOrClose,
+    CommaOrClose,
+    End,
+}
+
+pub fn parse_item(s: &str) -> Result<ListItem, ParseError> {
+    let mut open_lists: Vec<Vec<ListItem>> = Vec::new();
+    let mut result = None;
+    let mut expect = Expect::Item;
+
+    for token in Tokenizer::new(s) {
+        let (offset, token) = token?;
+        let unexpected = || ParseError::new(offset, ParseErrorKind::UnexpectedCharacter(s[offset..].chars().next().unwrap()));
+
+        let completed = match (token, expect) {
+            (Token::Open, Expect::Item | Expect::ItemOrClose) => {
+                open_lists.push(Vec::new());
+                expect = Expect::ItemOrClose;
+                None
+            },
+            (Token::Number(number), Expect::Item | Expect::ItemOrClose) => Some(ListItem::Number(number)),
+            (Token::Close, Expect::ItemOrClose | Expect::CommaOrClose) => {
+                let list = open_lists.pop().ok_or_else(unexpected)?;
+                Some(ListItem::NestedList(NestedList { list }))
+            },
+            (Token::Comma, Expect::CommaOrClose) => {
+                expect = Expect::Item;
+                None
+            },
+            _ => return Err(unexpected()),
+        };
+
+        if let Some(item) = completed {
+            match open_lists.last_mut() {
+                Some(list) => {
+                    list.push(item);
+                    expect = Expect::CommaOrClose;
+                },
+                None => {
+                    result = Some(item);
+                    expect = Expect::End;
+                },
+            }
+        }
+    }
+
+    result.ok_or(ParseError::new(s.len(), ParseErrorKind::UnexpectedEnd))
+}
+
+impl FromStr for ListItem {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_item(s)
+    }
+}
+
+impl FromStr for NestedList {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_item(s)? {
+            ListItem::NestedList(list) => Ok(list),
+            ListItem::Number(_) => Err(ParseError::new(s.len() - s.trim_start().len(), ParseErrorKind::ExpectedList)),
+        }
+    }
+}
+
+impl FromStr for ListPair {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = Vec::new();
+        let mut offset = 0;
+
+        for line in s.split('\n') {
+            if !line.trim().is_empty() {
+                lines.push((offset, line));
+            }
+            offset += line.len() + 1;
+        }
+
+        match lines[..] {
+            [(left_offset, left), (right_offset, right)] => {
+                let left = left.parse().map_err(|error: ParseError| error.shifted(left_offset))?;
+                let right = right.parse().map_err(|error: ParseError| error.shifted(right_offset))?;
+                Ok(ListPair { left, right })
+            },
+            [_, _, (extra_offset, _), ..] => Err(ParseError::new(extra_offset, ParseErrorKind::ExpectedPair)),
+            _ => Err(ParseError::new(s.len(), ParseErrorKind::ExpectedPair)),
+        }
+    }
+}
@@ -1,27 +1,70 @@
 use std::cell::RefCell;
+use std::io;
+use std::path::PathBuf;
 use std::rc::Rc;
 use crate::puzzle::*;
+use crate::puzzle::boundary::*;
 use crate::puzzle::cave::Cave;
-use crate::puzzle::bottomless_cave::BottomlessCave;
-use crate::puzzle::infinite_cave::InfiniteCave;
+use crate::puzzle::render::*;
+use crate::puzzle::schedule::*;
+use crate::puzzle::sand_cave::SandCave;
 
 mod puzzle;
 
 const SAND_SPAWN_POINT: Position = Position(500, 0);
 
+const FLOOR_OFFSET: i64 = 2;
+const PNG_CELL_SIZE: u32 = 4;
+
 fn main() {
     part_one();
     part_two();
+
+    let options = options_from_args();
+    if options.boundary.is_some() || options.render || options.frames_per_second.is_some() || options.png_path.is_some() || !options.sources.is_empty() || options.statistics {
+        custom_cave(&options);
+    }
 }
 
-fn input_to_bottomless_cave(input: &str) -> BottomlessCave {
-    let rock_shape: Vec<RockShape> = input.split("\n").filter(|line| !line.is_empty()).map(|line| line.parse().unwrap()).collect();
-    BottomlessCave::new(rock_shape)
+#[derive(Default)]
+struct Options {
+    boundary: Option<Boundary>,
+    render: bool,
+    statistics: bool,
+    frames_per_second: Option<u32>,
+    png_path: Option<PathBuf>,
+    sources: Vec<Source>,
+}
+
+fn options_from_args() -> Options {
+    let mut options = Options::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--render" => options.render = true,
+            "--stats" => options.statistics = true,
+            "--animate" => options.frames_per_second = Some(args.next().and_then(|fps| fps.parse().ok()).expect("frames per second expected")),
+            "--png" => options.png_path = Some(args.next().expect("png path expected").into()),
+            "--source" => options.sources.push(args.next().and_then(|source| source.parse().ok()).expect("source expected")),
+            boundary => options.boundary = Some(parse_boundary(boundary).expect("boundary expected")),
+        }
+    }
+
+    options
 }
 
-fn input_to_infinite_cave(input: &str) -> InfiniteCave {
+fn input_to_cave(input: &str, boundary: Boundary) -> SandCave {
     let rock_shape: Vec<RockShape> = input.split("\n").filter(|line| !line.is_empty()).map(|line| line.parse().unwrap()).collect();
-    InfiniteCave::new(rock_shape)
+    SandCave::new(rock_shape, boundary)
+}
+
+fn input_to_bottomless_cave(input: &str) -> SandCave {
+    input_to_cave(input, Boundary::ABYSS)
+}
+
+fn input_to_infinite_cave(input: &str) -> SandCave {
+    input_to_cave(input, Boundary::floor(FLOOR_OFFSET))
 }
 
 fn drop_until_full(cave: Rc<RefCell<dyn Cave>>) {
@@ -44,17 +87,66 @@ fn part_one() {
 
 fn part_two() {
     let input = day_util::read_input();
-    let cave = Rc::new(RefCell::new(input_to_infinite_cave(&input)));
+    let mut cave = input_to_infinite_cave(&input);
 
-    drop_until_full(cave.clone());
-    let sand_count = cave.borrow().sand_count();
+    let sand_count = cave.fill(SAND_SPAWN_POINT);
     println!("Sand count: {sand_count}");
 }
 
+fn custom_cave(options: &Options) {
+    let input = day_util::read_input();
+    let boundary = options.boundary.unwrap_or(Boundary::ABYSS);
+    let mut cave = input_to_cave(&input, boundary);
+
+    let sand_count = match options.frames_per_second {
+        None if !options.sources.is_empty() => {
+            let counts = run_schedule(&mut cave, &options.sources);
+            for (source, count) in options.sources.iter().zip(&counts) {
+                let position = source.position();
+                println!("Source {},{}: {count}", position.0, position.1);
+            }
+            counts.iter().sum()
+        },
+        Some(frames_per_second) => {
+            animate(&mut cave, SAND_SPAWN_POINT, &mut io::stdout(), frames_per_second).expect("terminal output failed");
+            cave.sand_count()
+        },
+        None => cave.fill(SAND_SPAWN_POINT),
+    };
+
+    if options.render {
+        print!("{}", render(&cave, SAND_SPAWN_POINT, None));
+    }
+    if let Some(png_path) = &options.png_path {
+        write_png(&cave, SAND_SPAWN_POINT, png_path, PNG_CELL_SIZE).expect("png export failed");
+    }
+    if options.statistics {
+        print_statistics(&cave);
+    }
+    println!("Particle count with {boundary:?}: {sand_count}");
+}
+
+fn print_statistics(cave: &SandCave) {
+    let (min, max) = cave.bounds();
+    println!("Rocks: x {}..={}, down to y {}", cave.left_bound(), cave.right_bound(), cave.lower_bound());
+    if let Some(floor_level) = cave.floor_level() {
+        println!("Floor: y {floor_level}");
+    }
+    println!("Bounds: ({},{})..=({},{})", min.0, min.1, max.0, max.1);
+
+    println!("Rock: {}", cave.rock_count());
+    println!("Sand: {}", cave.sand_count());
+    println!("Water: {}", cave.count(Filling::Water));
+    if let Some(deepest) = cave.resting_sand().max_by_key(|position| (position.1, position.0)) {
+        println!("Deepest resting sand: {},{}", deepest.0, deepest.1);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use super::cave::Cave;
+    use super::particle::*;
 
     const TEST_INPUT: &str = r"498,4 -> 498,6 -> 496,6
 503,4 -> 502,4 -> 502,9 -> 494,9
@@ -63,7 +155,7 @@ mod tests {
     #[test]
     fn parse_test() {
         let rock_shapes: Vec<RockShape> = TEST_INPUT.split("\n").filter(|line| !line.is_empty()).map(|line| line.parse().unwrap()).collect();
-        let known_rock_shapes = vec![RockShape::new(vec![Position(498, 4), Position(498, 6), Position(496, 6)]),
+        let known_rock_shapes = [RockShape::new(vec![Position(498, 4), Position(498, 6), Position(496, 6)]),
             RockShape::new(vec![Position(503, 4), Position(502, 4), Position(502, 9), Position(494, 9)])];
 
         assert_eq!(rock_shapes.len(), known_rock_shapes.len());
@@ -80,7 +172,7 @@ mod tests {
 
         let infinite_cave = input_to_infinite_cave(TEST_INPUT);
 
-        assert_eq!(infinite_cave.floor_level(), 11);
+        assert_eq!(infinite_cave.floor_level(), Some(11));
         assert_eq!(infinite_cave.rock_count(), 20);
     }
 
@@ -98,5 +190,187 @@ mod tests {
 
         drop_until_full(cave.clone());
         assert_eq!(cave.borrow().sand_count(), 93);
+
+        let mut filled_cave = input_to_infinite_cave(TEST_INPUT);
+        assert_eq!(filled_cave.fill(SAND_SPAWN_POINT), 93);
+        assert_eq!(filled_cave.sand_count(), 93);
+        assert!(!filled_cave.drop_sand(SAND_SPAWN_POINT));
+    }
+
+    #[test]
+    fn memoized_path_test() {
+        let mut partial_cave = input_to_bottomless_cave(TEST_INPUT);
+        for _ in 0..5 {
+            assert!(partial_cave.drop_sand(SAND_SPAWN_POINT));
+        }
+        assert!(partial_cave.drop_sand(Position(495, 0)));
+        assert_eq!(partial_cave.sand_count(), 6);
+        assert!(partial_cave.drop_sand(SAND_SPAWN_POINT));
+        assert_eq!(partial_cave.sand_count(), 7);
+    }
+
+    #[test]
+    fn boundary_test() {
+        assert_eq!(parse_boundary("abyss"), Ok(Boundary::ABYSS));
+        assert_eq!(parse_boundary("floor=2"), Ok(Boundary::floor(2)));
+        assert_eq!(parse_boundary("floor=3,walls"), Ok(Boundary::floor(3).with_sides(Sides::Walls)));
+        assert_eq!(parse_boundary("abyss,wrap"), Ok(Boundary::ABYSS.with_sides(Sides::Wrap)));
+        assert!(parse_boundary("abyssal").is_err());
+        assert!(parse_boundary("floor=0").is_err());
+        assert!(parse_boundary("floor=2,walls,wrap").is_err());
+
+        let mut origin_cave = input_to_cave("-1,2 -> 1,2\n", Boundary::ABYSS);
+        assert!(origin_cave.drop_sand(Position(0, 0)));
+        assert!(!origin_cave.drop_sand(Position(0, 0)));
+        assert_eq!(origin_cave.sand_count(), 1);
+
+        let mut negative_cave = input_to_cave("-3,-1 -> -1,-1\n", Boundary::floor(1));
+        assert_eq!(negative_cave.fill(Position(-2, -3)), 6);
+
+        let mut walled_cave = input_to_cave(TEST_INPUT, Boundary::floor(FLOOR_OFFSET).with_sides(Sides::Walls));
+        let walled_count = walled_cave.fill(SAND_SPAWN_POINT);
+        let walled_simulation = Rc::new(RefCell::new(input_to_cave(TEST_INPUT, Boundary::floor(FLOOR_OFFSET).with_sides(Sides::Walls))));
+        drop_until_full(walled_simulation.clone());
+        assert_eq!(walled_simulation.borrow().sand_count(), walled_count);
+        assert!(walled_count < 93);
+
+        let mut wrapped_cave = input_to_cave(TEST_INPUT, Boundary::floor(FLOOR_OFFSET).with_sides(Sides::Wrap));
+        let wrapped_count = wrapped_cave.fill(SAND_SPAWN_POINT);
+        let wrapped_simulation = Rc::new(RefCell::new(input_to_cave(TEST_INPUT, Boundary::floor(FLOOR_OFFSET).with_sides(Sides::Wrap))));
+        drop_until_full(wrapped_simulation.clone());
+        assert_eq!(wrapped_simulation.borrow().sand_count(), wrapped_count);
+        assert!(!wrapped_cave.drop_sand(Position(490, 0)));
+
+        let mut walled_abyss = input_to_cave(TEST_INPUT, Boundary::ABYSS.with_sides(Sides::Walls));
+        assert!(walled_abyss.fill(SAND_SPAWN_POINT) >= 24);
+    }
+
+    #[test]
+    fn render_test() {
+        let mut cave = input_to_bottomless_cave(TEST_INPUT);
+        assert_eq!(render(&cave, SAND_SPAWN_POINT, None), r#"......+...
+..........
+..........
+..........
+....#...##
+....#...#.
+..###...#.
+........#.
+........#.
+#########.
+"#);
+
+        let trace = cave.trace_sand(SAND_SPAWN_POINT);
+        assert_eq!(trace.first(), Some(&SAND_SPAWN_POINT));
+        assert_eq!(trace.last(), Some(&Position(500, 8)));
+        assert_eq!(render(&cave, SAND_SPAWN_POINT, Some(trace[3])).lines().nth(3), Some("......o..."));
+
+        cave.fill(SAND_SPAWN_POINT);
+        assert_eq!(render(&cave, SAND_SPAWN_POINT, None), r#"......+...
+..........
+......o...
+.....ooo..
+....#ooo##
+...o#ooo#.
+..###ooo#.
+....oooo#.
+.o.ooooo#.
+#########.
+"#);
+
+        let mut floor_cave = input_to_infinite_cave(TEST_INPUT);
+        floor_cave.fill(SAND_SPAWN_POINT);
+        let floor_render = render(&floor_cave, SAND_SPAWN_POINT, None);
+        assert_eq!(floor_render.lines().next(), Some("..........o.........."));
+        assert_eq!(floor_render.lines().last(), Some("#####################"));
+        assert_eq!(floor_render.matches('o').count(), 93);
+
+        let mut animated_cave = input_to_bottomless_cave(TEST_INPUT);
+        let mut frames = Vec::new();
+        animate(&mut animated_cave, SAND_SPAWN_POINT, &mut frames, 1000).unwrap();
+        let frames = String::from_utf8(frames).unwrap();
+        assert_eq!(animated_cave.sand_count(), 24);
+        assert!(frames.matches("\x1b[2J").count() > 24);
+
+        let path = std::env::temp_dir().join("day14_render_test.png");
+        write_png(&floor_cave, SAND_SPAWN_POINT, &path, 2).unwrap();
+        assert!(std::fs::read(&path).unwrap().starts_with(b"\x89PNG"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn schedule_test() {
+        let mut cave = input_to_bottomless_cave(TEST_INPUT);
+        let sources = [Source::new(SAND_SPAWN_POINT, Box::new(Sand), 1)];
+        assert_eq!(run_schedule(&mut cave, &sources), [24]);
+
+        let mut cave = input_to_infinite_cave(TEST_INPUT);
+        let sources: Vec<Source> = ["500,0", "497,0:sand:3"].iter().map(|source| source.parse().unwrap()).collect();
+        let counts = run_schedule(&mut cave, &sources);
+        assert_eq!(counts.len(), 2);
+        assert!(counts.iter().all(|&count| count > 0));
+        assert_eq!(counts.iter().sum::<usize>(), cave.sand_count());
+        let mut filled_cave = input_to_infinite_cave(TEST_INPUT);
+        filled_cave.fill(SAND_SPAWN_POINT);
+        assert!(cave.sand_count() >= filled_cave.sand_count());
+
+        assert!("500,0:lava".parse::<Source>().is_err());
+        assert!("500,0:sand:x".parse::<Source>().is_err());
+        assert!(parse_particle_rule("water=x").is_err());
+
+        let mut basin = input_to_cave("0,0 -> 0,3 -> 6,3 -> 6,0\n", Boundary::ABYSS);
+        let water = Water { spread: 10 };
+        assert_eq!(basin.trace_particle(Position(3, 0), &water), [Position(3, 0), Position(3, 1), Position(3, 2), Position(2, 2), Position(1, 2)]);
+        let sources = [Source::new(Position(3, 0), Box::new(Water { spread: 10 }), 1)];
+        assert_eq!(run_schedule(&mut basin, &sources), [15]);
+
+        let mut shared_spawn = input_to_cave("0,0 -> 0,3 -> 10,3 -> 10,0\n", Boundary::ABYSS);
+        assert!(shared_spawn.drop_particle(Position(5, 0), &water));
+        let narrow_water = Water { spread: 1 };
+        assert_eq!(shared_spawn.trace_particle(Position(5, 0), &narrow_water).last(), Some(&Position(4, 2)));
+        assert!(shared_spawn.drop_particle(Position(5, 0), &narrow_water));
+        let mut resting: Vec<Position> = shared_spawn.resting(Filling::Water).collect();
+        resting.sort_by_key(|position| position.0);
+        assert_eq!(resting, [Position(1, 2), Position(4, 2)]);
+        assert_eq!(parse_particle_rule("water=3").unwrap().name(), "water=3");
+        assert_eq!(render(&basin, Position(3, -1), None), r#"...+...
+#~~~~~#
+#~~~~~#
+#~~~~~#
+#######
+"#);
+
+        let mut narrow = input_to_cave("0,0 -> 0,3 -> 6,3 -> 6,0\n", Boundary::ABYSS);
+        let sources = [Source::new(Position(3, 0), Box::new(Water { spread: 1 }), 2), Source::new(Position(1, 0), Box::new(Sand), 1)];
+        let counts = run_schedule(&mut narrow, &sources);
+        let narrow_render = render(&narrow, Position(3, 0), None);
+        assert_eq!(narrow_render.matches('~').count(), counts[0]);
+        assert_eq!(narrow_render.matches('o').count(), counts[1]);
+        assert!(counts[0] > counts[1] && counts[1] > 0);
+    }
+
+    #[test]
+    fn query_test() {
+        let mut cave = input_to_bottomless_cave(TEST_INPUT);
+        cave.fill(SAND_SPAWN_POINT);
+
+        assert_eq!(cave.bounds(), (Position(494, 2), Position(503, 9)));
+        assert_eq!(cave.count(Filling::Rock), cave.rock_count());
+        assert_eq!(cave.count(Filling::Sand), 24);
+        assert_eq!(cave.count(Filling::Water), 0);
+        assert_eq!(cave.filling(&Position(500, 8)), Filling::Sand);
+        assert_eq!(cave.filling(&Position(502, 9)), Filling::Rock);
+        assert_eq!(cave.filling(&Position(500, 1)), Filling::Air);
+
+        let mut resting: Vec<Position> = cave.resting_sand().collect();
+        resting.sort_by_key(|position| (position.1, position.0));
+        assert_eq!(resting.len(), 24);
+        assert_eq!(resting.first(), Some(&Position(500, 2)));
+        assert_eq!(resting.last(), Some(&Position(501, 8)));
+        assert!(resting.iter().all(|position| cave.filling(position) == Filling::Sand));
+
+        let floor_cave = input_to_infinite_cave(TEST_INPUT);
+        assert_eq!(floor_cave.bounds(), (Position(494, 4), Position(503, 11)));
+        assert_eq!(floor_cave.filling(&Position(0, 11)), Filling::Rock);
     }
 }
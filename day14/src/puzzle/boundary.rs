@@ -0,0 +1,51 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bottom {
+    Abyss,
+    Floor(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sides {
+    Open,
+    Walls,
+    Wrap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Boundary {
+    pub bottom: Bottom,
+    pub sides: Sides,
+}
+
+impl Boundary {
+    pub const ABYSS: Boundary = Boundary { bottom: Bottom::Abyss, sides: Sides::Open };
+
+    pub fn floor(offset: i64) -> Self {
+        Boundary { bottom: Bottom::Floor(offset), sides: Sides::Open }
+    }
+
+    pub fn with_sides(self, sides: Sides) -> Self {
+        Boundary { sides, ..self }
+    }
+}
+
+pub fn parse_boundary(s: &str) -> Result<Boundary, ()> {
+    let mut parts = s.split(',');
+    let bottom_part = parts.next().ok_or(())?;
+    let boundary = match bottom_part.split_once('=') {
+        None if bottom_part == "abyss" => Boundary::ABYSS,
+        Some(("floor", offset)) => Boundary::floor(offset.parse().ok().filter(|&offset| offset > 0).ok_or(())?),
+        _ => return Err(()),
+    };
+    let sides = match parts.next() {
+        None | Some("open") => Sides::Open,
+        Some("walls") => Sides::Walls,
+        Some("wrap") => Sides::Wrap,
+        Some(_) => return Err(()),
+    };
+
+    match parts.next() {
+        None => Ok(boundary.with_sides(sides)),
+        Some(_) => Err(()),
+    }
+}
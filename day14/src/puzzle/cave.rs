@@ -1,9 +1,31 @@
 use super::*;
+use super::particle::*;
 
 pub trait Cave {
-    fn drop_sand(&mut self, drop_location: Position) -> bool;
-    fn sand_count(&self) -> usize;
+    fn drop_particle(&mut self, drop_location: Position, rule: &dyn ParticleRule) -> bool;
+    fn trace_particle(&self, drop_location: Position, rule: &dyn ParticleRule) -> Vec<Position>;
+    fn count(&self, filling: Filling) -> usize;
+    fn filling(&self, position: &Position) -> Filling;
+    fn bounds(&self) -> (Position, Position);
+    fn resting(&self, filling: Filling) -> Box<dyn Iterator<Item = Position> + '_>;
 
-    #[cfg(test)]
-    fn rock_count(&self) -> usize;
-}
\ No newline at end of file
+    fn drop_sand(&mut self, drop_location: Position) -> bool {
+        self.drop_particle(drop_location, &Sand)
+    }
+
+    fn trace_sand(&self, drop_location: Position) -> Vec<Position> {
+        self.trace_particle(drop_location, &Sand)
+    }
+
+    fn sand_count(&self) -> usize {
+        self.count(Filling::Sand)
+    }
+
+    fn rock_count(&self) -> usize {
+        self.count(Filling::Rock)
+    }
+
+    fn resting_sand(&self) -> Box<dyn Iterator<Item = Position> + '_> {
+        self.resting(Filling::Sand)
+    }
+}
@@ -1,11 +1,14 @@
+pub mod boundary;
 pub mod cave;
-pub mod bottomless_cave;
-pub mod infinite_cave;
+pub mod particle;
+pub mod render;
+pub mod sand_cave;
+pub mod schedule;
 
 use std::{str::FromStr, collections::HashSet};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Position(pub usize, pub usize);
+pub struct Position(pub i64, pub i64);
 
 impl Position {
     pub fn line_between(&self, other: &Self) -> Vec<Position> {
@@ -66,7 +69,6 @@ impl RockShape {
         }
     }
 
-    #[cfg(test)]
     pub fn new(line: Vec<Position>) -> Self {
         RockShape { line }
     }
@@ -78,13 +80,14 @@ impl FromStr for RockShape {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let line = s.split(" -> ").map(|position| position.parse().unwrap()).collect();
-        Ok(RockShape { line })
+        Ok(RockShape::new(line))
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Filling {
     Air,
     Rock,
     Sand,
+    Water,
 }
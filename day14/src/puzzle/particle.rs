@@ -0,0 +1,65 @@
+use super::*;
+
+pub trait ParticleRule {
+    fn name(&self) -> String;
+    fn filling(&self) -> Filling;
+    fn next_position(&self, trajectory: &[Position], is_free: &dyn Fn(Position) -> bool) -> Option<Position>;
+}
+
+pub struct Sand;
+
+impl ParticleRule for Sand {
+    fn name(&self) -> String {
+        String::from("sand")
+    }
+
+    fn filling(&self) -> Filling {
+        Filling::Sand
+    }
+
+    fn next_position(&self, trajectory: &[Position], is_free: &dyn Fn(Position) -> bool) -> Option<Position> {
+        let position = trajectory.last()?;
+        [0, -1, 1].into_iter().map(|dx| Position(position.0 + dx, position.1 + 1)).find(|&next| is_free(next))
+    }
+}
+
+pub struct Water {
+    pub spread: usize,
+}
+
+impl ParticleRule for Water {
+    fn name(&self) -> String {
+        format!("water={}", self.spread)
+    }
+
+    fn filling(&self) -> Filling {
+        Filling::Water
+    }
+
+    fn next_position(&self, trajectory: &[Position], is_free: &dyn Fn(Position) -> bool) -> Option<Position> {
+        let position = *trajectory.last()?;
+        let below = Position(position.0, position.1 + 1);
+        if is_free(below) {
+            return Some(below);
+        }
+
+        let sideways = trajectory.windows(2).rev().take_while(|pair| pair[0].1 == pair[1].1).count();
+        let directions = match trajectory.iter().rev().nth(1) {
+            Some(previous) if sideways > 0 => vec![(position.0 - previous.0).signum()],
+            _ => vec![-1, 1],
+        };
+
+        if sideways >= self.spread {
+            return None;
+        }
+        directions.into_iter().map(|dx| Position(position.0 + dx, position.1)).find(|&next| is_free(next))
+    }
+}
+
+pub fn parse_particle_rule(s: &str) -> Result<Box<dyn ParticleRule>, ()> {
+    match s.split_once('=') {
+        None if s == "sand" => Ok(Box::new(Sand)),
+        Some(("water", spread)) => Ok(Box::new(Water { spread: spread.parse().map_err(|_| ())? })),
+        _ => Err(()),
+    }
+}
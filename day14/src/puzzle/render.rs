@@ -0,0 +1,89 @@
+use std::{fs::File, io::{self, BufWriter, Write}, path::Path, thread, time::Duration};
+
+use super::*;
+use super::cave::Cave;
+
+const AIR: [u8; 3] = [0x1e, 0x1e, 0x2e];
+const ROCK: [u8; 3] = [0x58, 0x5b, 0x70];
+const SAND: [u8; 3] = [0xf9, 0xe2, 0xaf];
+const WATER: [u8; 3] = [0x89, 0xb4, 0xfa];
+const SOURCE: [u8; 3] = [0xf3, 0x8b, 0xa8];
+
+fn view_bounds(cave: &dyn Cave, spawn: Position) -> (Position, Position) {
+    let (min, max) = cave.bounds();
+    (Position(min.0.min(spawn.0), min.1.min(spawn.1)), Position(max.0.max(spawn.0), max.1.max(spawn.1)))
+}
+
+fn cell_symbol(cave: &dyn Cave, spawn: Position, falling: Option<Position>, position: Position) -> char {
+    match cave.filling(&position) {
+        Filling::Rock => '#',
+        Filling::Sand => 'o',
+        Filling::Water => '~',
+        Filling::Air if falling == Some(position) => 'o',
+        Filling::Air if position == spawn => '+',
+        Filling::Air => '.',
+    }
+}
+
+pub fn render(cave: &dyn Cave, spawn: Position, falling: Option<Position>) -> String {
+    let (min, max) = view_bounds(cave, spawn);
+    let mut output = String::new();
+
+    for y in min.1..=max.1 {
+        output.extend((min.0..=max.0).map(|x| cell_symbol(cave, spawn, falling, Position(x, y))));
+        output.push('\n');
+    }
+
+    output
+}
+
+pub fn animate(cave: &mut dyn Cave, spawn: Position, out: &mut impl Write, frames_per_second: u32) -> io::Result<()> {
+    let delay = Duration::from_secs_f64(1.0 / frames_per_second.max(1) as f64);
+
+    loop {
+        for position in cave.trace_sand(spawn) {
+            write!(out, "\x1b[2J\x1b[H{}", render(cave, spawn, Some(position)))?;
+            out.flush()?;
+            thread::sleep(delay);
+        }
+
+        if !cave.drop_sand(spawn) {
+            break;
+        }
+    }
+
+    write!(out, "\x1b[2J\x1b[H{}", render(cave, spawn, None))?;
+    out.flush()
+}
+
+pub fn write_png(cave: &dyn Cave, spawn: Position, path: &Path, cell_size: u32) -> io::Result<()> {
+    let (min, max) = view_bounds(cave, spawn);
+    let cell_size = cell_size.max(1);
+    let width = (max.0 - min.0 + 1) as u32 * cell_size;
+    let height = (max.1 - min.1 + 1) as u32 * cell_size;
+
+    let mut pixels = Vec::with_capacity((width * height * 3) as usize);
+    for y in min.1..=max.1 {
+        let row: Vec<u8> = (min.0..=max.0).flat_map(|x| {
+            let color = match cell_symbol(cave, spawn, None, Position(x, y)) {
+                '#' => ROCK,
+                'o' => SAND,
+                '~' => WATER,
+                '+' => SOURCE,
+                _ => AIR,
+            };
+            color.repeat(cell_size as usize)
+        }).collect();
+
+        for _ in 0..cell_size {
+            pixels.extend_from_slice(&row);
+        }
+    }
+
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(&pixels).map_err(io::Error::other)
+}
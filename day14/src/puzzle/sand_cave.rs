@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use super::*;
+use super::boundary::*;
+use super::cave::Cave;
+use super::particle::*;
+
+enum Step {
+    Fall(Position),
+    Rest,
+    Lost,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SandCave {
+    left_bound: i64,
+    right_bound: i64,
+    lower_bound: i64,
+    boundary: Boundary,
+    area: HashMap<Position, Filling>,
+    paths: HashMap<(Position, String), Vec<Position>>,
+}
+
+impl Cave for SandCave {
+    fn drop_particle(&mut self, drop_location: Position, rule: &dyn ParticleRule) -> bool {
+        let Some(drop_location) = self.confine(drop_location).filter(|position| !self.is_blocked(position)) else {
+            return false;
+        };
+
+        let key = (drop_location, rule.name());
+        let mut path = self.paths.remove(&key).unwrap_or_default();
+        if let Some(blocked) = path.iter().position(|position| self.is_blocked(position)) {
+            path.truncate(blocked);
+        }
+        if path.is_empty() {
+            path.push(drop_location);
+        }
+
+        let settled = loop {
+            match self.next_step(&path, rule) {
+                Step::Fall(next_position) => path.push(next_position),
+                Step::Rest => {
+                    let position = path.pop().unwrap();
+                    self.area.insert(position, rule.filling());
+                    break true;
+                },
+                Step::Lost => break false,
+            }
+        };
+
+        self.paths.insert(key, path);
+        settled
+    }
+
+    fn trace_particle(&self, drop_location: Position, rule: &dyn ParticleRule) -> Vec<Position> {
+        let Some(drop_location) = self.confine(drop_location).filter(|position| !self.is_blocked(position)) else {
+            return Vec::new();
+        };
+        let mut trace = vec![drop_location];
+
+        while let Step::Fall(next_position) = self.next_step(&trace, rule) {
+            trace.push(next_position);
+        }
+
+        trace
+    }
+
+    fn count(&self, filling: Filling) -> usize {
+        self.area.values().filter(|&element| *element == filling).count()
+    }
+
+    fn filling(&self, position: &Position) -> Filling {
+        let below_floor = self.floor_level().is_some_and(|floor_level| position.1 >= floor_level);
+        let beyond_walls = self.boundary.sides == Sides::Walls && !(self.left_bound..=self.right_bound).contains(&position.0);
+
+        if below_floor || beyond_walls {
+            Filling::Rock
+        } else {
+            self.area.get(position).copied().unwrap_or(Filling::Air)
+        }
+    }
+
+    fn bounds(&self) -> (Position, Position) {
+        let mut min = Position(self.left_bound, self.lower_bound);
+        let mut max = Position(self.right_bound, self.lower_bound);
+
+        for position in self.area.keys() {
+            min = Position(min.0.min(position.0), min.1.min(position.1));
+            max = Position(max.0.max(position.0), max.1.max(position.1));
+        }
+        if let Some(floor_level) = self.floor_level() {
+            max.1 = floor_level;
+        }
+        if self.boundary.sides == Sides::Walls {
+            min.0 = self.left_bound - 1;
+            max.0 = self.right_bound + 1;
+        }
+
+        (min, max)
+    }
+
+    fn resting(&self, filling: Filling) -> Box<dyn Iterator<Item = Position> + '_> {
+        Box::new(self.area.iter().filter(move |(_, element)| **element == filling).map(|(position, _)| *position))
+    }
+}
+
+impl SandCave {
+    pub fn new(rock_shapes: Vec<RockShape>, boundary: Boundary) -> Self {
+        let area: HashMap<Position, Filling> = rock_shapes.iter()
+            .flat_map(|rock_shape| rock_shape.rock_positions())
+            .map(|position| (position, Filling::Rock))
+            .collect();
+
+        let left_bound = area.keys().map(|pos| pos.0).min().unwrap_or(0);
+        let right_bound = area.keys().map(|pos| pos.0).max().unwrap_or(0);
+        let lower_bound = area.keys().map(|pos| pos.1).max().unwrap_or(0);
+
+        Self { left_bound, right_bound, lower_bound, boundary, area, paths: HashMap::new() }
+    }
+
+    pub fn fill(&mut self, drop_location: Position) -> usize {
+        if self.boundary.bottom == Bottom::Abyss {
+            while self.drop_sand(drop_location) {}
+            return self.sand_count();
+        }
+
+        let mut row: HashSet<Position> = self.confine(drop_location).filter(|position| !self.is_blocked(position)).into_iter().collect();
+
+        while !row.is_empty() {
+            let next_row: HashSet<Position> = row.iter()
+                .flat_map(|position| [-1, 0, 1].map(|dx| Position(position.0 + dx, position.1 + 1)))
+                .filter_map(|position| self.confine(position))
+                .filter(|position| !self.is_blocked(position))
+                .collect();
+
+            self.area.extend(row.into_iter().map(|position| (position, Filling::Sand)));
+            row = next_row;
+        }
+
+        self.paths.clear();
+        self.sand_count()
+    }
+
+    pub fn left_bound(&self) -> i64 {
+        self.left_bound
+    }
+
+    pub fn right_bound(&self) -> i64 {
+        self.right_bound
+    }
+
+    pub fn lower_bound(&self) -> i64 {
+        self.lower_bound
+    }
+
+    pub fn floor_level(&self) -> Option<i64> {
+        match self.boundary.bottom {
+            Bottom::Abyss => None,
+            Bottom::Floor(offset) => Some(self.lower_bound + offset),
+        }
+    }
+
+    fn next_step(&self, trajectory: &[Position], rule: &dyn ParticleRule) -> Step {
+        let Some(position) = trajectory.last() else {
+            return Step::Rest;
+        };
+        if self.boundary.bottom == Bottom::Abyss && position.1 >= self.lower_bound {
+            return Step::Lost;
+        }
+
+        let is_free = |next: Position| self.confine(next).is_some_and(|next| !self.is_blocked(&next));
+        match rule.next_position(trajectory, &is_free).and_then(|next| self.confine(next)) {
+            Some(next_position) => Step::Fall(next_position),
+            None => Step::Rest,
+        }
+    }
+
+    fn confine(&self, position: Position) -> Option<Position> {
+        match self.boundary.sides {
+            Sides::Open => Some(position),
+            Sides::Walls if (self.left_bound..=self.right_bound).contains(&position.0) => Some(position),
+            Sides::Walls => None,
+            Sides::Wrap => {
+                let width = self.right_bound - self.left_bound + 1;
+                Some(Position(self.left_bound + (position.0 - self.left_bound).rem_euclid(width), position.1))
+            },
+        }
+    }
+
+    fn is_blocked(&self, position: &Position) -> bool {
+        self.filling(position) != Filling::Air
+    }
+}
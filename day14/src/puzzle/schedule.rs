@@ -0,0 +1,57 @@
+use super::*;
+use super::cave::Cave;
+use super::particle::*;
+
+pub struct Source {
+    position: Position,
+    rule: Box<dyn ParticleRule>,
+    batch: usize,
+}
+
+impl Source {
+    pub fn new(position: Position, rule: Box<dyn ParticleRule>, batch: usize) -> Self {
+        Self { position, rule, batch: batch.max(1) }
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+}
+
+impl FromStr for Source {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let position = parts.next().ok_or(())?.parse()?;
+        let rule = parse_particle_rule(parts.next().unwrap_or("sand"))?;
+        let batch = parts.next().map_or(Ok(1), |batch| batch.parse().map_err(|_| ()))?;
+
+        match parts.next() {
+            None => Ok(Source::new(position, rule, batch)),
+            Some(_) => Err(()),
+        }
+    }
+}
+
+pub fn run_schedule(cave: &mut dyn Cave, sources: &[Source]) -> Vec<usize> {
+    let mut counts = vec![0; sources.len()];
+    let mut active = vec![true; sources.len()];
+
+    while active.iter().any(|&is_active| is_active) {
+        for (i, source) in sources.iter().enumerate() {
+            for _ in 0..source.batch {
+                if !active[i] {
+                    break;
+                }
+                if cave.drop_particle(source.position, source.rule.as_ref()) {
+                    counts[i] += 1;
+                } else {
+                    active[i] = false;
+                }
+            }
+        }
+    }
+
+    counts
+}
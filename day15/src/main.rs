@@ -1,14 +1,32 @@
 pub mod puzzle;
 
-use std::{collections::HashSet, ops::RangeInclusive};
+use std::{collections::HashSet, fs, path::Path};
 
 use puzzle::*;
+use puzzle::coverage::*;
+use puzzle::interval_set::*;
+use puzzle::render::*;
 
 const FREQUENCY_MULTIPLIER: i64 = 4_000_000;
+const SEARCH_END: i64 = 4_000_000;
+const IMAGE_SIZE: u32 = 1000;
 
 fn main() {
     part_one();
-    part_two();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [flag] if flag == "--rows" => part_two_by_rows(),
+        [flag, bounds @ ..] if flag == "--regions" && bounds.len() == 4 => print_uncovered_regions(&args_to_area(bounds)),
+        [flag, bounds @ ..] if flag == "--render" && bounds.len() == 4 => print_map(&args_to_area(bounds)),
+        [flag, path] if flag == "--svg" || flag == "--png" => export_map(Path::new(path), flag == "--png"),
+        _ => part_two(),
+    }
+}
+
+fn args_to_area(bounds: &[String]) -> Area {
+    let bounds: Vec<i64> = bounds.iter().map(|bound| bound.parse().expect("bounds expected")).collect();
+    Area::new(Coordinates::new(bounds[0], bounds[1]), Coordinates::new(bounds[2], bounds[3]))
 }
 
 fn input_to_sensors(input: &str) -> Vec<Sensor> {
@@ -16,61 +34,47 @@ fn input_to_sensors(input: &str) -> Vec<Sensor> {
     lines.map(|line| line.parse().unwrap()).collect()
 }
 
-fn collect_covered_spaces_in_line(sensors: &Vec<Sensor>, line: i64) -> usize {
-    let sensor_coverage = sensors.iter().map(|sensor| sensor.covered_in_line(line)).flatten();
-    let mut covered_set = HashSet::new();
-    
-    sensor_coverage.for_each(|cover| cover.for_each(|pos| { covered_set.insert(pos); }));
+fn collect_covered_spaces_in_line(sensors: &[Sensor], line: i64) -> usize {
+    let covered = combined_ranges_for_row(sensors, line);
+    let beacons_in_line: HashSet<i64> = sensors.iter()
+        .map(|sensor| sensor.closest_beacon())
+        .filter(|beacon| beacon.y() == line && covered.contains(beacon.x()))
+        .map(|beacon| beacon.x())
+        .collect();
     
-    sensors.iter().for_each(|sensor| {
-        if covered_set.contains(&sensor.closest_beacon().x()) && sensor.closest_beacon().y() == line {
-            covered_set.remove(&sensor.closest_beacon().x());
-        }
-    });
-    
-    covered_set.len()
+    covered.len() as usize - beacons_in_line.len()
 }
 
-fn combined_ranges_for_row(sensors: &Vec<Sensor>, line: i64) -> Vec<RangeInclusive<i64>> {
-    let mut sensor_coverage: Vec<_> = sensors.iter().flat_map(|sensor| sensor.covered_in_line(line)).collect();
-    sensor_coverage.sort_by_key(|cover| *cover.start());
-    
-    let mut combined = Vec::new();
-    combined.push(sensor_coverage[0].clone());
-    
-    for i in 1..sensor_coverage.len() {
-        let last_index = combined.len() - 1;
-        let last_range = &combined[last_index];
-        let new_range = &sensor_coverage[i];
-        
-        if last_range.end() >= new_range.start() {
-            if new_range.end() > last_range.end() {
-                combined[last_index] = *last_range.start()..=*new_range.end();
-            }
-        } else {
-            combined.push(new_range.clone());
-        }
-    }
-    
-    combined
+fn combined_ranges_for_row(sensors: &[Sensor], line: i64) -> IntervalSet {
+    CoverageEngine::new(sensors).row_coverage(line)
 }
 
-fn find_uncovered_coord(combined_range_rows: Vec<Vec<RangeInclusive<i64>>>, start: i64, end: i64) -> Coordinates {
+#[cfg(any(test, not(feature = "rayon")))]
+fn find_uncovered_coord(combined_range_rows: impl IntoIterator<Item = IntervalSet>, start: i64, end: i64) -> Option<Coordinates> {
     
-    for row_index in 0..combined_range_rows.len() {
-        let row = &combined_range_rows[row_index];
-        if row.len() > 1 {
-            dbg!(&row);
-            for i in 0..row.len() {
-                let sub_range = &row[i];
-                if *sub_range.end() >= start && *sub_range.end() <= end {
-                    return Coordinates::new(sub_range.end() + 1, row_index as i64);
-                }
-            }
+    for (row_offset, row) in combined_range_rows.into_iter().enumerate() {
+        if let Some(gap) = row.gaps(start..=end).first() {
+            return Some(Coordinates::new(*gap.start(), start + row_offset as i64));
         }
     }
 
-    Coordinates::new(0, 0)
+    None
+}
+
+#[cfg(not(feature = "rayon"))]
+fn scan_rows_for_uncovered_coord(sensors: &[Sensor], start: i64, end: i64) -> Option<Coordinates> {
+    let combined_cover_rows = (start..=end).map(|line| combined_ranges_for_row(sensors, line));
+    find_uncovered_coord(combined_cover_rows, start, end)
+}
+
+#[cfg(feature = "rayon")]
+fn scan_rows_for_uncovered_coord(sensors: &[Sensor], start: i64, end: i64) -> Option<Coordinates> {
+    use rayon::prelude::*;
+
+    (start..=end).into_par_iter().find_map_any(|line| {
+        let gaps = combined_ranges_for_row(sensors, line).gaps(start..=end);
+        gaps.first().map(|gap| Coordinates::new(*gap.start(), line))
+    })
 }
 
 fn tuning_frequency(coord: &Coordinates) -> i64 {
@@ -84,16 +88,61 @@ fn part_one() {
     let covered_count = collect_covered_spaces_in_line(&sensors, 2000000);
     println!("Covered in line: {covered_count}");
 }
+
+fn find_distress_beacon(sensors: &[Sensor], start: i64, end: i64) -> Result<Coordinates, SearchError> {
+    CoverageEngine::new(sensors).distress_beacon(&Area::square(start, end))
+}
+
 fn part_two() {
     let input = day_util::read_input_safe("day15");
     let sensors = input_to_sensors(&input);
-    let combined_cover_rows: Vec<Vec<RangeInclusive<i64>>> = (0..4_000_000).into_iter().map(|line| combined_ranges_for_row(&sensors, line)).collect();
-    let uncovered_coord = find_uncovered_coord(combined_cover_rows, 0, 4_000_000);
+    let uncovered_coord = find_distress_beacon(&sensors, 0, SEARCH_END).unwrap_or_else(|error| panic!("{error}"));
     let tuning_frequency = tuning_frequency(&uncovered_coord);
         
     println!("Frequency: {tuning_frequency}");
 }
 
+fn part_two_by_rows() {
+    let input = day_util::read_input_safe("day15");
+    let sensors = input_to_sensors(&input);
+    let uncovered_coord = scan_rows_for_uncovered_coord(&sensors, 0, SEARCH_END).expect("uncovered coordinate expected");
+    let tuning_frequency = tuning_frequency(&uncovered_coord);
+        
+    println!("Frequency: {tuning_frequency}");
+}
+
+fn print_uncovered_regions(area: &Area) {
+    let input = day_util::read_input_safe("day15");
+    let sensors = input_to_sensors(&input);
+
+    match CoverageEngine::new(&sensors).uncovered_regions(area) {
+        Uncovered::FullyCovered => println!("Fully covered"),
+        Uncovered::Regions(regions) => regions.iter().for_each(|region| {
+            println!("({}, {})..=({}, {})", region.min().x(), region.min().y(), region.max().x(), region.max().y());
+        }),
+    }
+}
+
+fn print_map(area: &Area) {
+    let input = day_util::read_input_safe("day15");
+    let sensors = input_to_sensors(&input);
+
+    print!("{}", render_ascii(&sensors, area));
+}
+
+fn export_map(path: &Path, as_png: bool) {
+    let input = day_util::read_input_safe("day15");
+    let sensors = input_to_sensors(&input);
+    let distress_beacon = find_distress_beacon(&sensors, 0, SEARCH_END).ok();
+    let area = Area::square(0, SEARCH_END);
+
+    if as_png {
+        write_png(&sensors, &area, distress_beacon, IMAGE_SIZE, path).expect("png export failed");
+    } else {
+        fs::write(path, render_svg(&sensors, &area, distress_beacon, IMAGE_SIZE)).expect("svg export failed");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,12 +174,138 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3
     #[test]
     fn part_two_test() {
         let sensors = input_to_sensors(TEST_INPUT);
-        let combined_cover_rows: Vec<Vec<RangeInclusive<i64>>> = (0..20).into_iter().map(|line| combined_ranges_for_row(&sensors, line)).collect();
-        let uncovered_coord = find_uncovered_coord(combined_cover_rows, 0, 20);
+        let combined_cover_rows: Vec<IntervalSet> = (0..=20).map(|line| combined_ranges_for_row(&sensors, line)).collect();
+        let uncovered_coord = find_uncovered_coord(combined_cover_rows, 0, 20).unwrap();
         let tuning_frequency = tuning_frequency(&uncovered_coord);
         
         assert_eq!(uncovered_coord.x(), 14);
         assert_eq!(uncovered_coord.y(), 11);
         assert_eq!(tuning_frequency, 56000011);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn row_scan_test() {
+        let sensors = input_to_sensors(TEST_INPUT);
+
+        assert_eq!(scan_rows_for_uncovered_coord(&sensors, 0, 20), Some(Coordinates::new(14, 11)));
+        assert_eq!(scan_rows_for_uncovered_coord(&sensors, 0, 10), None);
+
+        let engine = CoverageEngine::new(&sensors);
+        let any_gap = scan_rows_for_uncovered_coord(&sensors, -10, 30).unwrap();
+        assert!(Area::square(-10, 30).contains(&any_gap) && !engine.is_covered(&any_gap));
+    }
+
+    #[test]
+    fn interval_set_test() {
+        let mut set: IntervalSet = [1..=3, 10..=12, 5..=6].into_iter().collect();
+        assert_eq!(set.ranges(), [1..=3, 5..=6, 10..=12]);
+        assert_eq!(set.len(), 8);
+
+        set.insert(4..=4);
+        assert_eq!(set.ranges(), [1..=6, 10..=12]);
+        set.insert(8..=20);
+        assert_eq!(set.ranges(), [1..=6, 8..=20]);
+        set.insert(-5..=30);
+        assert_eq!(set.ranges(), [-5..=30]);
+        assert_eq!(set.len(), 36);
+
+        let left: IntervalSet = [0..=10, 20..=30].into_iter().collect();
+        let right: IntervalSet = [5..=22, 25..=25, 29..=40].into_iter().collect();
+        assert_eq!(left.union(&right).ranges(), [0..=40]);
+        assert_eq!(left.intersection(&right).ranges(), [5..=10, 20..=22, 25..=25, 29..=30]);
+        assert_eq!(left.subtract(&right).ranges(), [0..=4, 23..=24, 26..=28]);
+        assert_eq!(right.subtract(&left).ranges(), [11..=19, 31..=40]);
+        assert!(left.subtract(&left).is_empty());
+        assert_eq!(left.subtract(&IntervalSet::new()), left);
+
+        assert!(left.contains(0) && left.contains(25) && !left.contains(15) && !left.contains(31));
+        assert_eq!(left.gaps(-2..=35), [-2..=-1, 11..=19, 31..=35]);
+        assert!(left.gaps(0..=10).is_empty());
+
+        let sensors = input_to_sensors(TEST_INPUT);
+        assert_eq!(combined_ranges_for_row(&sensors, 10).ranges(), [-2..=24]);
+        assert_eq!(combined_ranges_for_row(&sensors, 11).gaps(0..=20), [14..=14]);
+    }
+
+    #[test]
+    fn coverage_engine_test() {
+        let sensors = input_to_sensors(TEST_INPUT);
+        let engine = CoverageEngine::new(&sensors);
+
+        assert_eq!(find_distress_beacon(&sensors, 0, 20), Ok(Coordinates::new(14, 11)));
+        assert!(!engine.is_covered(&Coordinates::new(14, 11)));
+        assert!(engine.is_covered(&Coordinates::new(14, 10)));
+        assert_eq!(engine.uncovered_corners(&Area::square(0, 20)), [Coordinates::new(14, 11)]);
+
+        let area = Area::new(Coordinates::new(-10, -5), Coordinates::new(30, 25));
+        let uncovered: Vec<Coordinates> = (-5..=25).flat_map(|y| (-10..=30).map(move |x| Coordinates::new(x, y)))
+            .filter(|coord| !engine.is_covered(coord))
+            .collect();
+        let corners = engine.uncovered_corners(&area);
+        assert!(corners.iter().all(|corner| uncovered.contains(corner)));
+        assert!(corners.contains(&Coordinates::new(-10, -5)));
+
+        let single_sensor = input_to_sensors("Sensor at x=0, y=0: closest beacon is at x=2, y=0\n");
+        let corners = CoverageEngine::new(&single_sensor).uncovered_corners(&Area::square(-2, 2));
+        assert_eq!(corners, [Coordinates::new(-2, -2), Coordinates::new(2, -2), Coordinates::new(-2, 2), Coordinates::new(2, 2)]);
+        assert!(CoverageEngine::new(&single_sensor).uncovered_corners(&Area::square(-1, 1)).is_empty());
+    }
+
+    #[test]
+    fn uncovered_regions_test() {
+        let sensors = input_to_sensors(TEST_INPUT);
+        let engine = CoverageEngine::new(&sensors);
+        let point = |x, y| Area::new(Coordinates::new(x, y), Coordinates::new(x, y));
+
+        assert_eq!(engine.uncovered_regions(&Area::square(0, 20)), Uncovered::Regions(vec![point(14, 11)]));
+        assert_eq!(engine.uncovered_regions(&Area::square(0, 10)), Uncovered::FullyCovered);
+        assert_eq!(find_distress_beacon(&sensors, 0, 10), Err(SearchError::FullyCovered));
+
+        let area = Area::new(Coordinates::new(8, -4), Coordinates::new(16, -1));
+        let Uncovered::Regions(regions) = engine.uncovered_regions(&area) else { panic!("uncovered regions expected") };
+        let cells: u64 = regions.iter().map(|region| (region.max().x() - region.min().x() + 1) as u64 * (region.max().y() - region.min().y() + 1) as u64).sum();
+        let brute_force = (-4..=-1).flat_map(|y| (8..=16).map(move |x| Coordinates::new(x, y))).filter(|coord| !engine.is_covered(coord)).count();
+        assert_eq!(cells, brute_force as u64);
+        assert_eq!(regions[0], Area::new(Coordinates::new(9, -4), Coordinates::new(16, -4)));
+        assert!(regions.contains(&point(12, -1)) && regions.contains(&point(14, -1)));
+
+        let strip = Area::new(Coordinates::new(-10, 0), Coordinates::new(-9, 5));
+        assert_eq!(engine.uncovered_regions(&strip), Uncovered::Regions(vec![strip]));
+
+        match find_distress_beacon(&sensors, 0, 25) {
+            Err(SearchError::MultipleCandidates(candidates)) => {
+                assert!(candidates.contains(&Coordinates::new(14, 11)));
+                assert!(candidates.iter().all(|candidate| !engine.is_covered(candidate)));
+            },
+            other => panic!("multiple candidates expected, got {other:?}"),
+        }
+        assert!(SearchError::MultipleCandidates(vec![Coordinates::new(1, 2), Coordinates::new(3, 4)]).to_string().ends_with("(1, 2), (3, 4)"));
+    }
+
+    #[test]
+    fn render_test() {
+        let sensors = input_to_sensors(TEST_INPUT);
+        let map = render_ascii(&sensors, &Area::new(Coordinates::new(-2, 9), Coordinates::new(25, 11)));
+
+        assert_eq!(map, r#".#########################..
+####B######################.
+##S#############.###########
+"#);
+
+        let svg = render_svg(&sensors, &Area::square(0, 20), Some(Coordinates::new(14, 11)), 200);
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<polygon").count(), sensors.len());
+        assert!(svg.contains("points=\"2,11 9,18 2,25 -5,18\""));
+        assert!(svg.contains("cx=\"14\" cy=\"11\" r=\"2\" fill=\"none\""));
+
+        let (width, height, pixels) = render_pixels(&sensors, &Area::square(0, 20), None, 21);
+        assert_eq!((width, height, pixels.len()), (21, 21, 21 * 21 * 3));
+        let offset = (11 * 21 + 14) * 3;
+        assert_eq!(pixels[offset..offset + 3], [0x1e, 0x1e, 0x2e]);
+
+        let path = std::env::temp_dir().join("day15_render_test.png");
+        write_png(&sensors, &Area::square(0, 20), Some(Coordinates::new(14, 11)), 100, &path).unwrap();
+        assert!(fs::read(&path).unwrap().starts_with(b"\x89PNG"));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+use std::fmt::Display;
+
+use super::*;
+use super::interval_set::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Area {
+    min: Coordinates,
+    max: Coordinates,
+}
+
+impl Area {
+    pub fn new(min: Coordinates, max: Coordinates) -> Self {
+        Self { min, max }
+    }
+
+    pub fn square(start: i64, end: i64) -> Self {
+        Self::new(Coordinates::new(start, start), Coordinates::new(end, end))
+    }
+
+    pub fn min(&self) -> Coordinates {
+        self.min
+    }
+
+    pub fn max(&self) -> Coordinates {
+        self.max
+    }
+
+    pub fn contains(&self, coord: &Coordinates) -> bool {
+        (self.min.x()..=self.max.x()).contains(&coord.x()) && (self.min.y()..=self.max.y()).contains(&coord.y())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Uncovered {
+    FullyCovered,
+    Regions(Vec<Area>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchError {
+    FullyCovered,
+    MultipleCandidates(Vec<Coordinates>),
+}
+
+impl Display for SearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchError::FullyCovered => write!(f, "search area is fully covered"),
+            SearchError::MultipleCandidates(candidates) => {
+                let candidates: Vec<String> = candidates.iter().map(|coord| format!("({}, {})", coord.x(), coord.y())).collect();
+                write!(f, "multiple candidates for the distress beacon: {}", candidates.join(", "))
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Line {
+    U(i64),
+    V(i64),
+    X(i64),
+    Y(i64),
+}
+
+impl Line {
+    fn doubled_intersection(&self, other: &Line) -> Option<(i64, i64)> {
+        match (*self, *other) {
+            (Line::U(u), Line::V(v)) | (Line::V(v), Line::U(u)) => Some((u + v, u - v)),
+            (Line::U(u), Line::X(x)) | (Line::X(x), Line::U(u)) => Some((2 * x, 2 * (u - x))),
+            (Line::U(u), Line::Y(y)) | (Line::Y(y), Line::U(u)) => Some((2 * (u - y), 2 * y)),
+            (Line::V(v), Line::X(x)) | (Line::X(x), Line::V(v)) => Some((2 * x, 2 * (x - v))),
+            (Line::V(v), Line::Y(y)) | (Line::Y(y), Line::V(v)) => Some((2 * (v + y), 2 * y)),
+            (Line::X(x), Line::Y(y)) | (Line::Y(y), Line::X(x)) => Some((2 * x, 2 * y)),
+            _ => None,
+        }
+    }
+}
+
+fn lattice_neighbours((doubled_x, doubled_y): (i64, i64)) -> impl Iterator<Item = Coordinates> {
+    let xs = [doubled_x.div_euclid(2), (doubled_x + 1).div_euclid(2)];
+    let ys = [doubled_y.div_euclid(2), (doubled_y + 1).div_euclid(2)];
+
+    xs.into_iter().flat_map(move |x| ys.into_iter().map(move |y| Coordinates::new(x, y)))
+}
+
+pub struct CoverageEngine<'a> {
+    sensors: &'a [Sensor],
+}
+
+impl<'a> CoverageEngine<'a> {
+    pub fn new(sensors: &'a [Sensor]) -> Self {
+        Self { sensors }
+    }
+
+    pub fn is_covered(&self, coord: &Coordinates) -> bool {
+        self.sensors.iter().any(|sensor| sensor.covers(coord))
+    }
+
+    pub fn row_coverage(&self, line: i64) -> IntervalSet {
+        self.sensors.iter().filter_map(|sensor| sensor.covered_in_line(line)).collect()
+    }
+
+    pub fn uncovered_regions(&self, area: &Area) -> Uncovered {
+        let mut regions = Vec::new();
+        let mut open: Vec<Area> = Vec::new();
+
+        for y in area.min.y()..=area.max.y() {
+            let gaps = self.row_coverage(y).gaps(area.min.x()..=area.max.x());
+            let (continued, closed): (Vec<Area>, Vec<Area>) = open.into_iter()
+                .partition(|region| gaps.contains(&(region.min.x()..=region.max.x())));
+            regions.extend(closed);
+
+            open = gaps.into_iter().map(|gap| {
+                match continued.iter().find(|region| region.min.x() == *gap.start() && region.max.x() == *gap.end()) {
+                    Some(region) => Area::new(region.min, Coordinates::new(region.max.x(), y)),
+                    None => Area::new(Coordinates::new(*gap.start(), y), Coordinates::new(*gap.end(), y)),
+                }
+            }).collect();
+        }
+        regions.extend(open);
+
+        if regions.is_empty() {
+            Uncovered::FullyCovered
+        } else {
+            regions.sort_by_key(|region| (region.min.y(), region.min.x()));
+            Uncovered::Regions(regions)
+        }
+    }
+
+    pub fn distress_beacon(&self, area: &Area) -> Result<Coordinates, SearchError> {
+        match self.uncovered_corners(area).as_slice() {
+            [] => Err(SearchError::FullyCovered),
+            [beacon] => Ok(*beacon),
+            candidates => Err(SearchError::MultipleCandidates(candidates.to_vec())),
+        }
+    }
+
+    /// Candidate points lie on the diagonals squeezed between two sensors'
+    /// boundaries, or on the edges of the area. Finding those diagonals takes
+    /// O(n²) time for n sensors; there are only a handful in practice, so
+    /// checking their intersections against every sensor stays cheap.
+    pub fn uncovered_corners(&self, area: &Area) -> Vec<Coordinates> {
+        let lines = self.gap_lines(area);
+
+        let mut corners: Vec<Coordinates> = lines.iter().enumerate()
+            .flat_map(|(i, line)| lines[i + 1..].iter().filter_map(move |other| line.doubled_intersection(other)))
+            .flat_map(lattice_neighbours)
+            .filter(|coord| area.contains(coord) && !self.is_covered(coord))
+            .collect();
+
+        corners.sort_by_key(|coord| (coord.y(), coord.x()));
+        corners.dedup();
+        corners
+    }
+
+    fn gap_lines(&self, area: &Area) -> Vec<Line> {
+        let mut lines = vec![Line::X(area.min.x()), Line::X(area.max.x()), Line::Y(area.min.y()), Line::Y(area.max.y())];
+        let diagonals: Vec<(i64, i64, i64)> = self.sensors.iter().map(|sensor| {
+            let position = sensor.position();
+            (position.x() + position.y(), position.x() - position.y(), sensor.coverage() as i64 + 1)
+        }).collect();
+
+        for (i, &(u, v, reach)) in diagonals.iter().enumerate() {
+            for &(other_u, other_v, other_reach) in &diagonals[i + 1..] {
+                if u + reach == other_u - other_reach || other_u + other_reach == u - reach {
+                    lines.push(Line::U(if u < other_u { u + reach } else { u - reach }));
+                }
+                if v + reach == other_v - other_reach || other_v + other_reach == v - reach {
+                    lines.push(Line::V(if v < other_v { v + reach } else { v - reach }));
+                }
+            }
+        }
+
+        lines
+    }
+}
@@ -0,0 +1,111 @@
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<RangeInclusive<i64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ranges(&self) -> &[RangeInclusive<i64>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|range| range.end().abs_diff(*range.start()) + 1).sum()
+    }
+
+    pub fn contains(&self, point: i64) -> bool {
+        let index = self.ranges.partition_point(|range| *range.end() < point);
+        self.ranges.get(index).is_some_and(|range| range.contains(&point))
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<i64>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let (mut start, mut end) = range.into_inner();
+        let first = self.ranges.partition_point(|existing| existing.end().saturating_add(1) < start);
+        let last = self.ranges.partition_point(|existing| *existing.start() <= end.saturating_add(1));
+
+        if first < last {
+            start = start.min(*self.ranges[first].start());
+            end = end.max(*self.ranges[last - 1].end());
+        }
+
+        self.ranges.splice(first..last, [start..=end]);
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut union = self.clone();
+        other.ranges.iter().for_each(|range| union.insert(range.clone()));
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intersection = IntervalSet::new();
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(left), Some(right)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = *left.start().max(right.start());
+            let end = *left.end().min(right.end());
+            if start <= end {
+                intersection.ranges.push(start..=end);
+            }
+
+            if left.end() < right.end() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        intersection
+    }
+
+    pub fn subtract(&self, other: &IntervalSet) -> IntervalSet {
+        let mut difference = IntervalSet::new();
+        let mut j = 0;
+
+        for range in &self.ranges {
+            while other.ranges.get(j).is_some_and(|removed| removed.end() < range.start()) {
+                j += 1;
+            }
+
+            let mut remaining = Some(*range.start());
+            for removed in other.ranges[j..].iter().take_while(|removed| removed.start() <= range.end()) {
+                let Some(start) = remaining else { break };
+                if *removed.start() > start {
+                    difference.ranges.push(start..=*removed.start() - 1);
+                }
+                remaining = if removed.end() >= range.end() { None } else { Some(start.max(*removed.end() + 1)) };
+            }
+
+            if let Some(start) = remaining {
+                difference.ranges.push(start..=*range.end());
+            }
+        }
+
+        difference
+    }
+
+    pub fn gaps(&self, bounds: RangeInclusive<i64>) -> Vec<RangeInclusive<i64>> {
+        IntervalSet::from_iter([bounds]).subtract(self).ranges
+    }
+}
+
+impl FromIterator<RangeInclusive<i64>> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = RangeInclusive<i64>>>(iter: T) -> Self {
+        let mut set = IntervalSet::new();
+        iter.into_iter().for_each(|range| set.insert(range));
+        set
+    }
+}
@@ -1,6 +1,10 @@
+pub mod coverage;
+pub mod interval_set;
+pub mod render;
+
 use std::{str::FromStr, ops::RangeInclusive};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Coordinates {
     x: i64,
     y: i64,
@@ -18,6 +22,10 @@ impl Coordinates {
     pub fn y(&self) -> i64 {
         self.y
     }
+
+    pub fn distance(&self, other: &Coordinates) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
 }
 
 impl FromStr for Coordinates {
@@ -62,6 +70,14 @@ impl Sensor {
         }
     }
 
+    pub fn covers(&self, coord: &Coordinates) -> bool {
+        self.position.distance(coord) <= self.coverage()
+    }
+
+    pub fn position(&self) -> Coordinates {
+        self.position
+    }
+
     pub fn closest_beacon(&self) -> Coordinates {
         self.closest_beacon
     }
@@ -0,0 +1,143 @@
+use std::{fs::File, io::{self, BufWriter}, path::Path};
+
+use super::*;
+use super::coverage::*;
+
+const BACKGROUND: [u8; 3] = [0x1e, 0x1e, 0x2e];
+const COVERED: [[u8; 3]; 4] = [
+    [0x45, 0x47, 0x5a],
+    [0x58, 0x5b, 0x70],
+    [0x3b, 0x4d, 0x6b],
+    [0x4f, 0x45, 0x6b],
+];
+const SENSOR: [u8; 3] = [0x89, 0xb4, 0xfa];
+const BEACON: [u8; 3] = [0xf9, 0xe2, 0xaf];
+const HIGHLIGHT: [u8; 3] = [0xf3, 0x8b, 0xa8];
+
+fn area_width(area: &Area) -> i64 {
+    area.max().x() - area.min().x() + 1
+}
+
+fn area_height(area: &Area) -> i64 {
+    area.max().y() - area.min().y() + 1
+}
+
+pub fn render_ascii(sensors: &[Sensor], area: &Area) -> String {
+    let engine = CoverageEngine::new(sensors);
+    let mut output = String::new();
+
+    for y in area.min().y()..=area.max().y() {
+        output.extend((area.min().x()..=area.max().x()).map(|x| {
+            let coord = Coordinates::new(x, y);
+            if sensors.iter().any(|sensor| sensor.position() == coord) {
+                'S'
+            } else if sensors.iter().any(|sensor| sensor.closest_beacon() == coord) {
+                'B'
+            } else if engine.is_covered(&coord) {
+                '#'
+            } else {
+                '.'
+            }
+        }));
+        output.push('\n');
+    }
+
+    output
+}
+
+fn hex(color: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+pub fn render_svg(sensors: &[Sensor], area: &Area, highlight: Option<Coordinates>, size: u32) -> String {
+    let (width, height) = (area_width(area), area_height(area));
+    let marker = (width.max(height) as f64 / 200.0).max(0.5);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{}\" viewBox=\"{} {} {width} {height}\">\n",
+        (size as i64 * height / width).max(1), area.min().x(), area.min().y(),
+    );
+    svg += &format!("<rect x=\"{}\" y=\"{}\" width=\"{width}\" height=\"{height}\" fill=\"{}\"/>\n", area.min().x(), area.min().y(), hex(BACKGROUND));
+
+    for (i, sensor) in sensors.iter().enumerate() {
+        let (x, y, reach) = (sensor.position().x(), sensor.position().y(), sensor.coverage() as i64);
+        svg += &format!(
+            "<polygon points=\"{x},{} {},{y} {x},{} {},{y}\" fill=\"{}\" fill-opacity=\"0.8\"/>\n",
+            y - reach, x + reach, y + reach, x - reach, hex(COVERED[i % COVERED.len()]),
+        );
+    }
+
+    for sensor in sensors {
+        let (position, beacon) = (sensor.position(), sensor.closest_beacon());
+        svg += &format!("<circle cx=\"{}\" cy=\"{}\" r=\"{marker}\" fill=\"{}\"/>\n", position.x(), position.y(), hex(SENSOR));
+        svg += &format!("<circle cx=\"{}\" cy=\"{}\" r=\"{marker}\" fill=\"{}\"/>\n", beacon.x(), beacon.y(), hex(BEACON));
+    }
+
+    if let Some(coord) = highlight {
+        svg += &format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>\n",
+            coord.x(), coord.y(), marker * 4.0, hex(HIGHLIGHT), marker,
+        );
+    }
+
+    svg + "</svg>\n"
+}
+
+pub fn render_pixels(sensors: &[Sensor], area: &Area, highlight: Option<Coordinates>, size: u32) -> (u32, u32, Vec<u8>) {
+    let (width, height) = (area_width(area), area_height(area));
+    let image_width = size.max(1);
+    let image_height = ((size as i64 * height / width) as u32).max(1);
+    let to_world = |pixel: u32, pixels: u32, min: i64, extent: i64| min + (pixel as i128 * extent as i128 / pixels as i128) as i64;
+
+    let mut pixels = Vec::with_capacity((image_width * image_height * 3) as usize);
+    for py in 0..image_height {
+        let y = to_world(py, image_height, area.min().y(), height);
+        for px in 0..image_width {
+            let coord = Coordinates::new(to_world(px, image_width, area.min().x(), width), y);
+            let color = match sensors.iter().position(|sensor| sensor.covers(&coord)) {
+                Some(i) => COVERED[i % COVERED.len()],
+                None => BACKGROUND,
+            };
+            pixels.extend_from_slice(&color);
+        }
+    }
+
+    let to_pixel = |coord: &Coordinates| {
+        let px = (coord.x() - area.min().x()) as i128 * image_width as i128 / width as i128;
+        let py = (coord.y() - area.min().y()) as i128 * image_height as i128 / height as i128;
+        (px as i64, py as i64)
+    };
+    let mut paint = |center: (i64, i64), radius: i64, color: [u8; 3], ring: bool| {
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let (px, py) = (center.0 + dx, center.1 + dy);
+                let distance = dx * dx + dy * dy;
+                let inside = distance <= radius * radius && (!ring || distance >= (radius - 1).pow(2));
+                if inside && (0..image_width as i64).contains(&px) && (0..image_height as i64).contains(&py) {
+                    let offset = ((py * image_width as i64 + px) * 3) as usize;
+                    pixels[offset..offset + 3].copy_from_slice(&color);
+                }
+            }
+        }
+    };
+
+    let marker = (image_width.max(image_height) as i64 / 250).max(1);
+    for sensor in sensors {
+        paint(to_pixel(&sensor.position()), marker, SENSOR, false);
+        paint(to_pixel(&sensor.closest_beacon()), marker, BEACON, false);
+    }
+    if let Some(coord) = highlight {
+        paint(to_pixel(&coord), marker * 4, HIGHLIGHT, true);
+    }
+
+    (image_width, image_height, pixels)
+}
+
+pub fn write_png(sensors: &[Sensor], area: &Area, highlight: Option<Coordinates>, size: u32, path: &Path) -> io::Result<()> {
+    let (width, height, pixels) = render_pixels(sensors, area, highlight, size);
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(&pixels).map_err(io::Error::other)
+}